@@ -56,6 +56,28 @@ fn rpo256_sequential(c: &mut Criterion) {
     });
 }
 
+fn rpo256_permutation(c: &mut Criterion) {
+    let state: [Felt; 12] = core::array::from_fn(|i| Felt::new(i as u64));
+    c.bench_function("RPO256 permutation (cached)", |bench| {
+        bench.iter(|| {
+            let mut state = black_box(state);
+            Rpo256::apply_permutation(&mut state);
+            state
+        })
+    });
+
+    c.bench_function("RPO256 permutation (random)", |bench| {
+        bench.iter_batched(
+            || core::array::from_fn::<Felt, 12, _>(|_| Felt::new(rand_value())),
+            |mut state| {
+                Rpo256::apply_permutation(&mut state);
+                state
+            },
+            BatchSize::SmallInput,
+        )
+    });
+}
+
 fn rpx256_2to1(c: &mut Criterion) {
     let v: [RpxDigest; 2] = [Rpx256::hash(&[1_u8]), Rpx256::hash(&[2_u8])];
     c.bench_function("RPX256 2-to-1 hashing (cached)", |bench| {
@@ -155,6 +177,7 @@ criterion_group!(
     rpx256_sequential,
     rpo256_2to1,
     rpo256_sequential,
+    rpo256_permutation,
     blake3_2to1,
     blake3_sequential
 );
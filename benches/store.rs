@@ -2,8 +2,8 @@ use criterion::{black_box, criterion_group, criterion_main, BatchSize, Benchmark
 use miden_crypto::{
     hash::rpo::RpoDigest,
     merkle::{
-        DefaultMerkleStore as MerkleStore, LeafIndex, MerkleTree, NodeIndex, SimpleSmt,
-        SMT_MAX_DEPTH,
+        DefaultMerkleStore as MerkleStore, InnerNodeInfo, LeafIndex, MerkleTree, NodeIndex,
+        SimpleSmt, SMT_MAX_DEPTH,
     },
     Felt, Word,
 };
@@ -381,6 +381,72 @@ fn new(c: &mut Criterion) {
     }
 }
 
+/// Benchmarks ingesting the nodes of a snapshot into a store, with and without pre-allocating
+/// space for the known number of nodes.
+fn ingest_snapshot(c: &mut Criterion) {
+    let mut group = c.benchmark_group("ingest_snapshot");
+
+    for size in BATCH_SIZES {
+        let leaves: Vec<Word> = (0..size).map(|_| random_word()).collect();
+        let nodes: Vec<InnerNodeInfo> = MerkleTree::new(leaves).unwrap().inner_nodes().collect();
+
+        group.bench_function(BenchmarkId::new("MerkleStore::new", size), |b| {
+            b.iter_batched(
+                || nodes.clone(),
+                |nodes| {
+                    let mut store = MerkleStore::new();
+                    store.extend(nodes);
+                    black_box(store)
+                },
+                BatchSize::SmallInput,
+            )
+        });
+
+        group.bench_function(BenchmarkId::new("MerkleStore::with_capacity", size), |b| {
+            b.iter_batched(
+                || nodes.clone(),
+                |nodes| {
+                    let mut store = MerkleStore::with_capacity(nodes.len());
+                    store.extend(nodes);
+                    black_box(store)
+                },
+                BatchSize::SmallInput,
+            )
+        });
+
+        #[cfg(feature = "indexmap")]
+        {
+            type IndexMapStore = miden_crypto::merkle::MerkleStore<
+                indexmap::IndexMap<RpoDigest, miden_crypto::merkle::StoreNode>,
+            >;
+
+            group.bench_function(BenchmarkId::new("IndexMapStore::new", size), |b| {
+                b.iter_batched(
+                    || nodes.clone(),
+                    |nodes| {
+                        let mut store = IndexMapStore::new();
+                        store.extend(nodes);
+                        black_box(store)
+                    },
+                    BatchSize::SmallInput,
+                )
+            });
+
+            group.bench_function(BenchmarkId::new("IndexMapStore::with_capacity", size), |b| {
+                b.iter_batched(
+                    || nodes.clone(),
+                    |nodes| {
+                        let mut store = IndexMapStore::with_capacity(nodes.len());
+                        store.extend(nodes);
+                        black_box(store)
+                    },
+                    BatchSize::SmallInput,
+                )
+            });
+        }
+    }
+}
+
 /// Benchmarks updating a leaf on MerkleTree and MerkleStore backends.
 fn update_leaf_merkletree(c: &mut Criterion) {
     let mut group = c.benchmark_group("update_leaf_merkletree");
@@ -480,6 +546,7 @@ criterion_group!(
     get_node_merkletree,
     get_node_of_empty_simplesmt,
     get_node_simplesmt,
+    ingest_snapshot,
     new,
     update_leaf_merkletree,
     update_leaf_simplesmt,
@@ -1,14 +1,16 @@
 use alloc::string::String;
 use core::{
+    fmt::{Display, LowerHex},
     mem::{size_of, transmute, transmute_copy},
     ops::Deref,
     slice::from_raw_parts,
+    str::FromStr,
 };
 
 use super::{Digest, ElementHasher, Felt, FieldElement, Hasher};
 use crate::utils::{
-    bytes_to_hex_string, hex_to_bytes, ByteReader, ByteWriter, Deserializable,
-    DeserializationError, HexParseError, Serializable,
+    bytes_to_hex_string, ct_eq_bytes, fmt_hex, hex_to_bytes, ByteReader, ByteWriter,
+    Deserializable, DeserializationError, HexParseError, Serializable,
 };
 
 #[cfg(test)]
@@ -33,6 +35,17 @@ const DIGEST20_BYTES: usize = 20;
 #[cfg_attr(feature = "serde", serde(into = "String", try_from = "&str"))]
 pub struct Blake3Digest<const N: usize>([u8; N]);
 
+impl<const N: usize> Blake3Digest<N> {
+    /// Returns true if this digest is equal to `other`, in time independent of the values of the
+    /// digests.
+    ///
+    /// This should be used instead of the derived [PartialEq] to compare digests derived from
+    /// secrets, such as the outputs of [Blake3_256::keyed_hash()].
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        ct_eq_bytes(&self.0, &other.0)
+    }
+}
+
 impl<const N: usize> Default for Blake3Digest<N> {
     fn default() -> Self {
         Self([0; N])
@@ -65,6 +78,20 @@ impl<const N: usize> From<Blake3Digest<N>> for String {
     }
 }
 
+impl<const N: usize> Display for Blake3Digest<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:#x}", self)
+    }
+}
+
+impl<const N: usize> LowerHex for Blake3Digest<N> {
+    /// Writes the hexadecimal digits of the digest, prefixed with `0x` when the alternate flag is
+    /// set (i.e., `{:#x}`).
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_hex(&self.0, f)
+    }
+}
+
 impl<const N: usize> TryFrom<&str> for Blake3Digest<N> {
     type Error = HexParseError;
 
@@ -73,6 +100,15 @@ impl<const N: usize> TryFrom<&str> for Blake3Digest<N> {
     }
 }
 
+impl<const N: usize> FromStr for Blake3Digest<N> {
+    type Err = HexParseError;
+
+    /// Expects the string to start with `0x`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.try_into()
+    }
+}
+
 impl<const N: usize> Serializable for Blake3Digest<N> {
     fn write_into<W: ByteWriter>(&self, target: &mut W) {
         target.write_bytes(&self.0);
@@ -155,6 +191,64 @@ impl Blake3_256 {
     {
         <Self as ElementHasher>::hash_elements(elements)
     }
+
+    /// Returns a keyed hash of the provided sequence of bytes, which can be used as a message
+    /// authentication code.
+    #[inline(always)]
+    pub fn keyed_hash(key: &[u8; DIGEST32_BYTES], bytes: &[u8]) -> Blake3Digest<DIGEST32_BYTES> {
+        Blake3Digest(blake3::keyed_hash(key, bytes).into())
+    }
+
+    /// Returns a 32-byte key derived from the provided key material.
+    ///
+    /// The context string should be hardcoded, globally unique and application-specific, so that
+    /// keys derived for different purposes from the same material are independent.
+    #[inline(always)]
+    pub fn derive_key(context: &str, key_material: &[u8]) -> [u8; DIGEST32_BYTES] {
+        blake3::derive_key(context, key_material)
+    }
+
+    /// Fills `output` with the extendable output of hashing the provided sequence of bytes.
+    ///
+    /// The output may have any length, and its first 32 bytes are equal to the output of
+    /// [Blake3_256::hash()] for the same input.
+    pub fn hash_xof(bytes: &[u8], output: &mut [u8]) {
+        Self::xof(bytes).fill(output);
+    }
+
+    /// Returns a reader over the extendable output of hashing the provided sequence of bytes.
+    pub fn xof(bytes: &[u8]) -> Blake3Xof {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(bytes);
+        Blake3Xof(hasher.finalize_xof())
+    }
+}
+
+// BLAKE3 EXTENDABLE OUTPUT
+// ================================================================================================
+
+/// A reader over the extendable output of the blake3 hash function, produced by
+/// [Blake3_256::xof()].
+///
+/// The output is effectively unbounded, and successive reads return consecutive bytes of it.
+#[derive(Debug, Clone)]
+pub struct Blake3Xof(blake3::OutputReader);
+
+impl Blake3Xof {
+    /// Fills `buf` with the next bytes of the output.
+    pub fn fill(&mut self, buf: &mut [u8]) {
+        self.0.fill(buf);
+    }
+
+    /// Returns the position in the output of the next byte to be read.
+    pub fn position(&self) -> u64 {
+        self.0.position()
+    }
+
+    /// Moves the reader to the specified position in the output.
+    pub fn set_position(&mut self, position: u64) {
+        self.0.set_position(position);
+    }
 }
 
 // BLAKE3 192-BIT OUTPUT
@@ -19,6 +19,70 @@ fn blake3_hash_elements() {
     assert_eq!(&expected, &actual);
 }
 
+#[test]
+fn blake3_keyed_hash_and_derive_key() {
+    let key = Blake3_256::derive_key("miden-crypto 2024-05 blake3 test key", b"key material");
+    assert_eq!(key, blake3::derive_key("miden-crypto 2024-05 blake3 test key", b"key material"));
+    assert_ne!(key, Blake3_256::derive_key("miden-crypto 2024-05 other key", b"key material"));
+
+    let mac = Blake3_256::keyed_hash(&key, b"message");
+    assert_eq!(*mac, *blake3::keyed_hash(&key, b"message").as_bytes());
+    assert_ne!(mac, Blake3_256::hash(b"message"));
+    assert!(mac.ct_eq(&Blake3_256::keyed_hash(&key, b"message")));
+    assert!(!mac.ct_eq(&Blake3_256::keyed_hash(&key, b"other message")));
+}
+
+#[test]
+fn blake3_xof() {
+    let bytes = rand_vector::<u8>(100);
+
+    // the first 32 bytes of the extendable output are the regular hash
+    let mut output = [0_u8; 100];
+    Blake3_256::hash_xof(&bytes, &mut output);
+    assert_eq!(output[..32], *Blake3_256::hash(&bytes));
+
+    // reading the output in several steps or from a given position yields the same bytes
+    let mut xof = Blake3_256::xof(&bytes);
+    let mut chunk = [0_u8; 40];
+    xof.fill(&mut chunk);
+    assert_eq!(chunk, output[..40]);
+    xof.fill(&mut chunk);
+    assert_eq!(chunk, output[40..80]);
+    assert_eq!(xof.position(), 80);
+
+    xof.set_position(10);
+    xof.fill(&mut chunk);
+    assert_eq!(chunk, output[10..50]);
+}
+
+#[test]
+fn blake3_digest_hex_formatting() {
+    let digest = Blake3Digest::<20>::from([0xab; 20]);
+    let hex = "ab".repeat(20);
+    assert_eq!(format!("{digest:x}"), hex);
+    assert_eq!(format!("{digest:#x}"), format!("0x{hex}"));
+    assert_eq!(format!("{digest}"), format!("0x{hex}"));
+    assert_eq!(format!("0x{hex}").parse::<Blake3Digest<20>>().unwrap(), digest);
+
+    // parsing is strict about the prefix, the length and the characters
+    assert_eq!(
+        hex.parse::<Blake3Digest<20>>(),
+        Err(HexParseError::InvalidLength { expected: 42, actual: 40 })
+    );
+    assert_eq!(
+        format!("0x{hex}").parse::<Blake3Digest<32>>(),
+        Err(HexParseError::InvalidLength { expected: 66, actual: 42 })
+    );
+    assert_eq!(
+        format!("1x{hex}").parse::<Blake3Digest<20>>(),
+        Err(HexParseError::MissingPrefix)
+    );
+    assert_eq!(
+        format!("0x{}g", &hex[1..]).parse::<Blake3Digest<20>>(),
+        Err(HexParseError::InvalidChar)
+    );
+}
+
 proptest! {
     #[test]
     fn blake160_wont_panic_with_arbitrary_input(ref vec in any::<Vec<u8>>()) {
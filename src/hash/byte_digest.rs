@@ -0,0 +1,275 @@
+// BYTE DIGEST
+// ================================================================================================
+
+/// Defines a digest type wrapping the 32-byte output of a byte-oriented hash function, together
+/// with its conversions, formatting and serialization.
+///
+/// Each hash function gets its own digest type, so that digests computed by different hash
+/// functions cannot be mixed up.
+macro_rules! byte_digest {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+        #[cfg_attr(feature = "serde", serde(into = "alloc::string::String", try_from = "&str"))]
+        pub struct $name([u8; 32]);
+
+        impl core::ops::Deref for $name {
+            type Target = [u8];
+
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl From<$name> for [u8; 32] {
+            fn from(value: $name) -> Self {
+                value.0
+            }
+        }
+
+        impl From<[u8; 32]> for $name {
+            fn from(value: [u8; 32]) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$crate::Word> for $name {
+            /// Encodes each element of the word as 8 little-endian bytes.
+            fn from(value: $crate::Word) -> Self {
+                let mut bytes = [0_u8; 32];
+                for (chunk, element) in bytes.chunks_exact_mut(8).zip(value.iter()) {
+                    chunk.copy_from_slice(&$crate::StarkField::as_int(*element).to_le_bytes());
+                }
+                Self(bytes)
+            }
+        }
+
+        impl TryFrom<$name> for $crate::Word {
+            type Error = $crate::utils::DigestDecodingError;
+
+            /// Decodes each 8 little-endian bytes of the digest as an element of the word.
+            ///
+            /// Returns an error if any of them is not the canonical encoding of a field element,
+            /// which is the case for most digests produced by hashing.
+            fn try_from(value: $name) -> Result<Self, Self::Error> {
+                let mut word = $crate::Word::default();
+                for (element, chunk) in word.iter_mut().zip(value.0.chunks_exact(8)) {
+                    let int = u64::from_le_bytes(chunk.try_into().expect("chunk is 8 bytes long"));
+                    if int >= <$crate::Felt as $crate::StarkField>::MODULUS {
+                        return Err($crate::utils::DigestDecodingError::NonCanonicalElement);
+                    }
+                    *element = $crate::Felt::new(int);
+                }
+                Ok(word)
+            }
+        }
+
+        impl From<$name> for ::alloc::string::String {
+            fn from(value: $name) -> Self {
+                $crate::utils::bytes_to_hex_string(value.0)
+            }
+        }
+
+        impl core::fmt::Display for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{:#x}", self)
+            }
+        }
+
+        impl core::fmt::LowerHex for $name {
+            /// Writes the hexadecimal digits of the digest, prefixed with `0x` when the alternate
+            /// flag is set (i.e., `{:#x}`).
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                $crate::utils::fmt_hex(&self.0, f)
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = $crate::utils::HexParseError;
+
+            fn try_from(value: &str) -> Result<Self, Self::Error> {
+                $crate::utils::hex_to_bytes(value).map(Self)
+            }
+        }
+
+        impl core::str::FromStr for $name {
+            type Err = $crate::utils::HexParseError;
+
+            /// Expects the string to start with `0x`.
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                s.try_into()
+            }
+        }
+
+        impl $crate::utils::Serializable for $name {
+            fn write_into<W: $crate::utils::ByteWriter>(&self, target: &mut W) {
+                target.write_bytes(&self.0);
+            }
+        }
+
+        impl $crate::utils::Deserializable for $name {
+            fn read_from<R: $crate::utils::ByteReader>(
+                source: &mut R,
+            ) -> Result<Self, $crate::utils::DeserializationError> {
+                source.read_array().map(Self)
+            }
+        }
+
+        impl $crate::hash::Digest for $name {
+            fn as_bytes(&self) -> [u8; 32] {
+                self.0
+            }
+        }
+    };
+}
+
+pub(super) use byte_digest;
+
+// BYTE HASHER
+// ================================================================================================
+
+/// Defines a hasher computing digests with a byte-oriented hash function, whose digest type must
+/// be defined by [byte_digest] in the same module.
+///
+/// The backend must implement the `Digest` trait of the `digest` crate, which must be in scope
+/// where this macro is invoked.
+macro_rules! byte_hasher {
+    ($(#[$meta:meta])* $name:ident, $digest:ident, $backend:ty) => {
+        $(#[$meta])*
+        #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+        pub struct $name;
+
+        impl $crate::hash::Hasher for $name {
+            /// The collision resistance is 128-bits for 32-bytes output.
+            const COLLISION_RESISTANCE: u32 = 128;
+
+            type Digest = $digest;
+
+            fn hash(bytes: &[u8]) -> Self::Digest {
+                $digest(<$backend>::digest(bytes).into())
+            }
+
+            fn merge(values: &[Self::Digest; 2]) -> Self::Digest {
+                let mut hasher = <$backend>::new();
+                hasher.update(values[0].0);
+                hasher.update(values[1].0);
+                $digest(hasher.finalize().into())
+            }
+
+            fn merge_with_int(seed: Self::Digest, value: u64) -> Self::Digest {
+                let mut hasher = <$backend>::new();
+                hasher.update(seed.0);
+                hasher.update(value.to_le_bytes());
+                $digest(hasher.finalize().into())
+            }
+        }
+
+        impl $crate::hash::ElementHasher for $name {
+            type BaseField = $crate::Felt;
+
+            /// Hashes the canonical little-endian encodings of the base field elements.
+            fn hash_elements<E>(elements: &[E]) -> Self::Digest
+            where
+                E: $crate::FieldElement<BaseField = Self::BaseField>,
+            {
+                let mut hasher = <$backend>::new();
+                for element in E::slice_as_base_elements(elements) {
+                    hasher.update($crate::StarkField::as_int(*element).to_le_bytes());
+                }
+                $digest(hasher.finalize().into())
+            }
+        }
+
+        impl $name {
+            /// Returns a hash of the provided sequence of bytes.
+            #[inline(always)]
+            pub fn hash(bytes: &[u8]) -> $digest {
+                <Self as $crate::hash::Hasher>::hash(bytes)
+            }
+
+            /// Returns a hash of two digests. This method is intended for use in construction of
+            /// Merkle trees and verification of Merkle paths.
+            #[inline(always)]
+            pub fn merge(values: &[$digest; 2]) -> $digest {
+                <Self as $crate::hash::Hasher>::merge(values)
+            }
+
+            /// Returns a hash of the provided field elements.
+            #[inline(always)]
+            pub fn hash_elements<E>(elements: &[E]) -> $digest
+            where
+                E: $crate::FieldElement<BaseField = $crate::Felt>,
+            {
+                <Self as $crate::hash::ElementHasher>::hash_elements(elements)
+            }
+        }
+    };
+}
+
+pub(super) use byte_hasher;
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use ::sha2::Digest as _;
+    use alloc::{string::String, vec::Vec};
+    use rand_utils::rand_vector;
+
+    use super::{byte_digest, byte_hasher};
+    use crate::{
+        utils::{Deserializable, DigestDecodingError, Serializable},
+        Felt, StarkField, Word,
+    };
+
+    byte_digest! {
+        TestDigest
+    }
+
+    byte_hasher! {
+        TestHasher, TestDigest, ::sha2::Sha256
+    }
+
+    #[test]
+    fn test_byte_hasher_merge() {
+        let a = TestHasher::hash(b"a");
+        let b = TestHasher::hash(b"b");
+
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&a);
+        bytes.extend_from_slice(&b);
+        assert_eq!(TestHasher::merge(&[a, b]), TestHasher::hash(&bytes));
+    }
+
+    #[test]
+    fn test_byte_hasher_hash_elements() {
+        let elements = rand_vector::<Felt>(17);
+
+        let mut bytes = Vec::new();
+        for element in elements.iter() {
+            bytes.extend_from_slice(&element.as_int().to_le_bytes());
+        }
+        assert_eq!(TestHasher::hash_elements(&elements), TestHasher::hash(&bytes));
+    }
+
+    #[test]
+    fn test_byte_digest_conversions() {
+        let word: Word = rand_vector::<Felt>(4).try_into().unwrap();
+        let digest = TestDigest::from(word);
+        assert_eq!(Word::try_from(digest).unwrap(), word);
+
+        // digests whose bytes don't encode canonical field elements can't be converted into words
+        let digest = TestDigest::from([u8::MAX; 32]);
+        assert!(matches!(Word::try_from(digest), Err(DigestDecodingError::NonCanonicalElement)));
+
+        let digest = TestHasher::hash(b"abc");
+        let hex = String::from(digest);
+        assert_eq!(format!("{digest}"), hex);
+        assert_eq!(format!("{digest:x}"), hex[2..]);
+        assert_eq!(TestDigest::try_from(hex.as_str()).unwrap(), digest);
+        assert_eq!(hex.parse::<TestDigest>().unwrap(), digest);
+        assert_eq!(TestDigest::read_from_bytes(&digest.to_bytes()).unwrap(), digest);
+    }
+}
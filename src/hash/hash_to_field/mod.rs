@@ -0,0 +1,43 @@
+use alloc::vec::Vec;
+
+use super::{Felt, StarkField};
+
+#[cfg(test)]
+mod tests;
+
+// HASH TO FIELD
+// ================================================================================================
+
+/// Returns `count` field elements derived from the provided message, in the provided domain.
+///
+/// The domain is length-prefixed and absorbed together with the message into BLAKE3, whose
+/// extendable output is then split into 8-byte little-endian integers. Integers which are not
+/// smaller than the field modulus are rejected, so that, modeling BLAKE3 as a random oracle, the
+/// returned elements are exactly uniformly distributed and independent. By contrast, reducing
+/// 64-bit integers modulo the field modulus would make the smallest 2^32 - 1 elements twice as
+/// likely as the others, a statistical distance of about 2^-32 per element.
+///
+/// An integer is rejected with probability (2^32 - 1) / 2^64 < 2^-32, so rejections barely affect
+/// the cost of this function. Since the elements are read in order from the same output, the
+/// elements returned for a given count are a prefix of those returned for any larger count.
+///
+/// Distinct protocols, and distinct uses within a protocol, should use distinct domains.
+pub fn hash_to_felts(domain: &[u8], msg: &[u8], count: usize) -> Vec<Felt> {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&(domain.len() as u64).to_le_bytes());
+    hasher.update(domain);
+    hasher.update(msg);
+    let mut output = hasher.finalize_xof();
+
+    let mut elements = Vec::with_capacity(count);
+    let mut bytes = [0_u8; 8];
+    while elements.len() < count {
+        output.fill(&mut bytes);
+        let value = u64::from_le_bytes(bytes);
+        if value < Felt::MODULUS {
+            elements.push(Felt::new(value));
+        }
+    }
+
+    elements
+}
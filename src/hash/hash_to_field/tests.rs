@@ -0,0 +1,28 @@
+use super::*;
+
+#[test]
+fn hash_to_felts_test_vector() {
+    let expected = [
+        Felt::new(10312345243814576835),
+        Felt::new(11881892547825440501),
+        Felt::new(7220788927612630869),
+        Felt::new(13982879316260332432),
+    ];
+    assert_eq!(hash_to_felts(b"test domain", b"message", 4), expected);
+}
+
+#[test]
+fn hash_to_felts_properties() {
+    let elements = hash_to_felts(b"test domain", b"message", 20);
+    assert_eq!(elements.len(), 20);
+    assert_eq!(elements, hash_to_felts(b"test domain", b"message", 20));
+    assert!(hash_to_felts(b"test domain", b"message", 0).is_empty());
+
+    // fewer elements are a prefix of more elements
+    assert_eq!(hash_to_felts(b"test domain", b"message", 7), elements[..7]);
+
+    // the domain and the message are not simply concatenated
+    assert_ne!(hash_to_felts(b"test domai", b"nmessage", 20), elements);
+    assert_ne!(hash_to_felts(b"other domain", b"message", 20), elements);
+    assert_ne!(hash_to_felts(b"test domain", b"other message", 20), elements);
+}
@@ -0,0 +1,25 @@
+use sha3::Digest as _;
+
+use super::byte_digest::{byte_digest, byte_hasher};
+
+#[cfg(test)]
+mod tests;
+
+// KECCAK256 DIGEST
+// ================================================================================================
+
+byte_digest! {
+    /// 32-bytes output of the Keccak256 hash function.
+    Keccak256Digest
+}
+
+// KECCAK256 HASHER
+// ================================================================================================
+
+byte_hasher! {
+    /// Keccak256 hasher, as used by Ethereum.
+    ///
+    /// This is the original Keccak submission with a 256-bit output, which differs from the
+    /// standardized SHA3-256 in its padding.
+    Keccak256, Keccak256Digest, sha3::Keccak256
+}
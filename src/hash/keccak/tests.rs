@@ -0,0 +1,16 @@
+use super::*;
+
+#[test]
+fn keccak256_test_vectors() {
+    let vectors = [
+        ("", "0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"),
+        ("abc", "0x4e03657aea45a94fc7d47ba826c8d667c0d1e6e33a64a036ec44f58fa12d6c45"),
+    ];
+
+    for (input, expected) in vectors {
+        let digest = Keccak256::hash(input.as_bytes());
+        assert_eq!(digest, expected.parse().unwrap());
+        assert_eq!(format!("{digest}"), expected);
+        assert_eq!(format!("{digest:x}"), expected[2..]);
+    }
+}
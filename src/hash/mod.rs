@@ -3,16 +3,25 @@
 use super::{CubeExtension, Felt, FieldElement, StarkField, ONE, ZERO};
 
 pub mod blake;
+mod byte_digest;
+pub mod keccak;
+pub mod sha2;
 
 mod rescue;
 pub mod rpo {
-    pub use super::rescue::{Rpo256, RpoDigest};
+    pub use super::rescue::{Rpo256, Rpo256ByteState, Rpo256State, RpoDigest};
 }
 
 pub mod rpx {
     pub use super::rescue::{Rpx256, RpxDigest};
 }
 
+mod hash_to_field;
+pub use hash_to_field::hash_to_felts;
+
+mod writer;
+pub use writer::{HasherWriter, IncrementalHasher};
+
 // RE-EXPORTS
 // ================================================================================================
 
@@ -43,7 +43,7 @@ pub mod optimized {
     }
 }
 
-#[cfg(target_feature = "avx2")]
+#[cfg(any(target_feature = "avx2", all(target_arch = "x86_64", feature = "std")))]
 mod x86_64_avx2;
 
 #[cfg(target_feature = "avx2")]
@@ -79,7 +79,74 @@ pub mod optimized {
     }
 }
 
-#[cfg(not(any(target_feature = "avx2", target_feature = "sve")))]
+// When the code is compiled for x86_64 without the `avx2` target feature, the AVX2 kernels are
+// still compiled, and used if the CPU running the code turns out to support AVX2. Detecting CPU
+// features at runtime requires the standard library.
+#[cfg(all(target_arch = "x86_64", feature = "std", not(target_feature = "avx2")))]
+pub mod optimized {
+    use super::x86_64_avx2::{apply_inv_sbox, apply_sbox};
+    use crate::{
+        hash::rescue::{add_constants, STATE_WIDTH},
+        Felt,
+    };
+
+    #[inline(always)]
+    pub fn add_constants_and_apply_sbox(
+        state: &mut [Felt; STATE_WIDTH],
+        ark: &[Felt; STATE_WIDTH],
+    ) -> bool {
+        // the result of the detection is cached by the standard library
+        if !std::is_x86_feature_detected!("avx2") {
+            return false;
+        }
+
+        add_constants(state, ark);
+        unsafe {
+            apply_sbox_avx2(std::mem::transmute(state));
+        }
+        true
+    }
+
+    #[inline(always)]
+    pub fn add_constants_and_apply_inv_sbox(
+        state: &mut [Felt; STATE_WIDTH],
+        ark: &[Felt; STATE_WIDTH],
+    ) -> bool {
+        if !std::is_x86_feature_detected!("avx2") {
+            return false;
+        }
+
+        add_constants(state, ark);
+        unsafe {
+            apply_inv_sbox_avx2(std::mem::transmute(state));
+        }
+        true
+    }
+
+    /// Applies the S-box using AVX2 instructions.
+    ///
+    /// # Safety
+    /// The CPU running the code must support AVX2.
+    #[target_feature(enable = "avx2")]
+    unsafe fn apply_sbox_avx2(state: &mut [u64; STATE_WIDTH]) {
+        apply_sbox(state)
+    }
+
+    /// Applies the inverse S-box using AVX2 instructions.
+    ///
+    /// # Safety
+    /// The CPU running the code must support AVX2.
+    #[target_feature(enable = "avx2")]
+    unsafe fn apply_inv_sbox_avx2(state: &mut [u64; STATE_WIDTH]) {
+        apply_inv_sbox(state)
+    }
+}
+
+#[cfg(not(any(
+    target_feature = "avx2",
+    target_feature = "sve",
+    all(target_arch = "x86_64", feature = "std")
+)))]
 pub mod optimized {
     use crate::{hash::rescue::STATE_WIDTH, Felt};
 
@@ -11,7 +11,7 @@ mod mds;
 use mds::{apply_mds, MDS};
 
 mod rpo;
-pub use rpo::{Rpo256, RpoDigest};
+pub use rpo::{Rpo256, Rpo256ByteState, Rpo256State, RpoDigest};
 
 mod rpx;
 pub use rpx::{Rpx256, RpxDigest};
@@ -1,3 +1,4 @@
+use alloc::vec::Vec;
 use core::ops::Range;
 
 use super::{
@@ -6,10 +7,14 @@ use super::{
     ARK2, BINARY_CHUNK_SIZE, CAPACITY_RANGE, DIGEST_BYTES, DIGEST_RANGE, DIGEST_SIZE, INPUT1_RANGE,
     INPUT2_RANGE, MDS, NUM_ROUNDS, ONE, RATE_RANGE, RATE_WIDTH, STATE_WIDTH, ZERO,
 };
+use crate::{Word, WORD_SIZE};
 
 mod digest;
 pub use digest::RpoDigest;
 
+mod state;
+pub use state::{Rpo256ByteState, Rpo256State};
+
 #[cfg(test)]
 mod tests;
 
@@ -171,43 +176,7 @@ impl ElementHasher for Rpo256 {
     fn hash_elements<E: FieldElement<BaseField = Self::BaseField>>(elements: &[E]) -> Self::Digest {
         // convert the elements into a list of base field elements
         let elements = E::slice_as_base_elements(elements);
-
-        // initialize state to all zeros, except for the first element of the capacity part, which
-        // is set to 1 if the number of elements is not a multiple of RATE_WIDTH.
-        let mut state = [ZERO; STATE_WIDTH];
-        if elements.len() % RATE_WIDTH != 0 {
-            state[CAPACITY_RANGE.start] = ONE;
-        }
-
-        // absorb elements into the state one by one until the rate portion of the state is filled
-        // up; then apply the Rescue permutation and start absorbing again; repeat until all
-        // elements have been absorbed
-        let mut i = 0;
-        for &element in elements.iter() {
-            state[RATE_RANGE.start + i] = element;
-            i += 1;
-            if i % RATE_WIDTH == 0 {
-                Self::apply_permutation(&mut state);
-                i = 0;
-            }
-        }
-
-        // if we absorbed some elements but didn't apply a permutation to them (would happen when
-        // the number of elements is not a multiple of RATE_WIDTH), apply the RPO permutation after
-        // padding by appending a 1 followed by as many 0 as necessary to make the input length a
-        // multiple of the RATE_WIDTH.
-        if i > 0 {
-            state[RATE_RANGE.start + i] = ONE;
-            i += 1;
-            while i != RATE_WIDTH {
-                state[RATE_RANGE.start + i] = ZERO;
-                i += 1;
-            }
-            Self::apply_permutation(&mut state);
-        }
-
-        // return the first 4 elements of the state as hash result
-        RpoDigest::new(state[DIGEST_RANGE].try_into().unwrap())
+        Self::hash_exact_iter(elements.iter().copied(), elements.len())
     }
 }
 
@@ -265,10 +234,138 @@ impl Rpo256 {
         <Self as ElementHasher>::hash_elements(elements)
     }
 
+    /// Returns a hash of the provided field elements prefixed by their number.
+    ///
+    /// [hash_elements()](Rpo256::hash_elements) binds the length of its input only through padding,
+    /// and returns a digest of all ZEROs for an empty input. When variable-length data is committed
+    /// to, this function should be used instead so that the number of elements is bound
+    /// explicitly. The length is counted in base field elements.
+    pub fn hash_elements_with_len<E: FieldElement<BaseField = Felt>>(elements: &[E]) -> RpoDigest {
+        let elements = E::slice_as_base_elements(elements);
+
+        // the length prefix is absorbed directly, without copying the elements after it
+        let len = Felt::new(elements.len() as u64);
+        Self::hash_exact_iter(
+            core::iter::once(len).chain(elements.iter().copied()),
+            elements.len() + 1,
+        )
+    }
+
+    /// Returns a hash of the elements yielded by the provided iterator, as computed by
+    /// [hash_elements()](Rpo256::hash_elements), without collecting them first.
+    ///
+    /// The elements are hashed as they are yielded, which requires their number to be known in
+    /// advance. Elements yielded by iterators of unknown length can be absorbed into an
+    /// [Rpo256State] instead.
+    ///
+    /// # Panics
+    /// Panics if the iterator yields a different number of elements than its reported length.
+    pub fn hash_iter<I>(elements: I) -> RpoDigest
+    where
+        I: IntoIterator<Item = Felt>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let elements = elements.into_iter();
+        let len = elements.len();
+        Self::hash_exact_iter(elements, len)
+    }
+
+    /// Returns a hash of the elements of the words yielded by the provided iterator, as computed
+    /// by [hash_elements()](Rpo256::hash_elements), without collecting them first.
+    ///
+    /// # Panics
+    /// Panics if the iterator yields a different number of words than its reported length.
+    pub fn hash_words_iter<I>(words: I) -> RpoDigest
+    where
+        I: IntoIterator<Item = Word>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let words = words.into_iter();
+        let len = words.len() * WORD_SIZE;
+        Self::hash_exact_iter(words.flatten(), len)
+    }
+
+    /// Returns a hash of any number of digests.
+    ///
+    /// The digests are absorbed two at a time, so the result is the same as the hash of their
+    /// elements computed by [hash_elements()](Rpo256::hash_elements); in particular, merging two
+    /// digests is the same as [merge()](Rpo256::merge), and merging no digests returns a digest
+    /// of all ZEROs.
+    pub fn merge_many(values: &[RpoDigest]) -> RpoDigest {
+        // the number of elements is a multiple of the rate only when the number of digests is even
+        let mut state = [ZERO; STATE_WIDTH];
+        if values.len() % 2 == 1 {
+            state[CAPACITY_RANGE.start] = ONE;
+        }
+
+        for pair in values.chunks(2) {
+            state[INPUT1_RANGE].copy_from_slice(pair[0].as_elements());
+            match pair.get(1) {
+                Some(second) => state[INPUT2_RANGE].copy_from_slice(second.as_elements()),
+                None => {
+                    // pad the last digest with a 1 followed by zeros
+                    state[INPUT2_RANGE.start] = ONE;
+                    state[INPUT2_RANGE.start + 1..INPUT2_RANGE.end].fill(ZERO);
+                }
+            }
+            Self::apply_permutation(&mut state);
+        }
+
+        RpoDigest::new(state[DIGEST_RANGE].try_into().unwrap())
+    }
+
+    /// Returns the hashes of the provided inputs, as computed by
+    /// [hash_elements()](Rpo256::hash_elements).
+    ///
+    /// This is meant for hashing large batches of leaves when building commitment trees. With the
+    /// `rayon` feature, the inputs are hashed using multiple threads.
+    pub fn hash_many<T: AsRef<[Felt]> + Sync>(inputs: &[T]) -> Vec<RpoDigest> {
+        #[cfg(not(feature = "rayon"))]
+        let digests = inputs.iter().map(|input| Self::hash_elements(input.as_ref())).collect();
+
+        #[cfg(feature = "rayon")]
+        let digests = {
+            use rayon::prelude::*;
+            inputs.par_iter().map(|input| Self::hash_elements(input.as_ref())).collect()
+        };
+
+        digests
+    }
+
+    /// Returns the result of [merge()](Rpo256::merge) applied to each consecutive pair of the
+    /// provided digests, i.e., the parents of a level of nodes of a Merkle tree.
+    ///
+    /// With the `rayon` feature, the pairs are merged using multiple threads.
+    ///
+    /// # Panics
+    /// Panics if the number of digests is odd.
+    pub fn merge_slice(values: &[RpoDigest]) -> Vec<RpoDigest> {
+        assert!(values.len() % 2 == 0, "the number of digests must be even");
+
+        #[cfg(not(feature = "rayon"))]
+        let digests = values.chunks_exact(2).map(|pair| Self::merge(&[pair[0], pair[1]])).collect();
+
+        #[cfg(feature = "rayon")]
+        let digests = {
+            use rayon::prelude::*;
+            values
+                .par_chunks_exact(2)
+                .map(|pair| Self::merge(&[pair[0], pair[1]]))
+                .collect()
+        };
+
+        digests
+    }
+
     // DOMAIN IDENTIFIER
     // --------------------------------------------------------------------------------------------
 
     /// Returns a hash of two digests and a domain identifier.
+    ///
+    /// The domain is injected into the second capacity element of the state, so merging the same
+    /// digests in different domains yields unrelated results. This allows different kinds of
+    /// trees to use distinct domains, preventing nodes of one kind from being passed off as nodes
+    /// of another. Merging in domain [ZERO] is the same as [Rpo256::merge].
     pub fn merge_in_domain(values: &[RpoDigest; 2], domain: Felt) -> RpoDigest {
         // initialize the state by copying the digest elements into the rate portion of the state
         // (8 total elements), and set the capacity elements to 0.
@@ -315,4 +412,50 @@ impl Rpo256 {
             apply_inv_sbox(state);
         }
     }
+
+    // HELPER FUNCTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a hash of the elements yielded by an iterator known to yield `len` elements, as
+    /// computed by [hash_elements()](Rpo256::hash_elements).
+    ///
+    /// # Panics
+    /// Panics if the iterator yields a different number of elements than `len`.
+    fn hash_exact_iter(elements: impl Iterator<Item = Felt>, len: usize) -> RpoDigest {
+        // initialize state to all zeros, except for the first element of the capacity part, which
+        // is set to 1 if the number of elements is not a multiple of RATE_WIDTH.
+        let mut state = [ZERO; STATE_WIDTH];
+        if len % RATE_WIDTH != 0 {
+            state[CAPACITY_RANGE.start] = ONE;
+        }
+
+        // absorb elements into the state one by one until the rate portion of the state is filled
+        // up; then apply the Rescue permutation and start absorbing again; repeat until all
+        // elements have been absorbed
+        let mut i = 0;
+        let mut count = 0;
+        for element in elements {
+            state[RATE_RANGE.start + i] = element;
+            count += 1;
+            i += 1;
+            if i == RATE_WIDTH {
+                Self::apply_permutation(&mut state);
+                i = 0;
+            }
+        }
+        assert_eq!(count, len, "iterator yielded a different number of elements than expected");
+
+        // if we absorbed some elements but didn't apply a permutation to them (would happen when
+        // the number of elements is not a multiple of RATE_WIDTH), apply the RPO permutation after
+        // padding by appending a 1 followed by as many 0 as necessary to make the input length a
+        // multiple of the RATE_WIDTH.
+        if i > 0 {
+            state[RATE_RANGE.start + i] = ONE;
+            state[RATE_RANGE.start + i + 1..RATE_RANGE.end].fill(ZERO);
+            Self::apply_permutation(&mut state);
+        }
+
+        // return the first 4 elements of the state as hash result
+        RpoDigest::new(state[DIGEST_RANGE].try_into().unwrap())
+    }
 }
@@ -0,0 +1,167 @@
+use super::{
+    Felt, Rpo256, RpoDigest, BINARY_CHUNK_SIZE, CAPACITY_RANGE, DIGEST_RANGE, ONE, RATE_RANGE,
+    RATE_WIDTH, STATE_WIDTH, ZERO,
+};
+
+// INCREMENTAL HASHER
+// ================================================================================================
+
+/// An incremental RPO sponge, computing the same digest as [Rpo256::hash_elements] over elements
+/// absorbed in any number of calls.
+///
+/// [Rpo256::hash_elements] sets the first capacity element depending on whether the number of
+/// elements is a multiple of the rate, which is not known until all the elements have been
+/// absorbed. The sponge therefore keeps one state for each case and discards the wrong one when
+/// squeezed, so absorbing elements costs twice as many permutations as hashing them at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rpo256State {
+    /// The sponge state for inputs whose length is a multiple of the rate, and the one for all
+    /// other inputs.
+    states: [[Felt; STATE_WIDTH]; 2],
+
+    /// The position in the rate portion of the state at which the next element is absorbed.
+    pos: usize,
+}
+
+impl Rpo256State {
+    /// Returns a new sponge which has not absorbed any element yet.
+    pub fn new() -> Self {
+        let mut states = [[ZERO; STATE_WIDTH]; 2];
+        states[1][CAPACITY_RANGE.start] = ONE;
+        Self { states, pos: 0 }
+    }
+
+    /// Absorbs the provided elements into the sponge.
+    pub fn absorb(&mut self, elements: &[Felt]) {
+        for &element in elements {
+            self.absorb_element(element);
+        }
+    }
+
+    /// Returns the digest of all the elements absorbed by the sponge.
+    pub fn squeeze(self) -> RpoDigest {
+        // the number of elements is a multiple of the rate iff all of them were permuted
+        let is_rate_multiple = self.pos == 0;
+        self.squeeze_with_flag(!is_rate_multiple)
+    }
+
+    // HELPER FUNCTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the digest of all the elements absorbed by the sponge, using the state whose first
+    /// capacity element was set to ONE if `flag` is true, or left to ZERO otherwise.
+    ///
+    /// The elements absorbed since the last permutation, if any, are padded with a 1 followed by
+    /// zeros.
+    fn squeeze_with_flag(self, flag: bool) -> RpoDigest {
+        let mut state = self.states[flag as usize];
+        if self.pos != 0 {
+            state[RATE_RANGE.start + self.pos] = ONE;
+            state[RATE_RANGE.start + self.pos + 1..RATE_RANGE.end].fill(ZERO);
+            Rpo256::apply_permutation(&mut state);
+        }
+
+        RpoDigest::new(state[DIGEST_RANGE].try_into().unwrap())
+    }
+
+    /// Absorbs a single element into the sponge.
+    fn absorb_element(&mut self, element: Felt) {
+        for state in self.states.iter_mut() {
+            state[RATE_RANGE.start + self.pos] = element;
+        }
+        self.pos += 1;
+
+        // the rate portion of the state is filled up, so apply the permutation
+        if self.pos == RATE_WIDTH {
+            for state in self.states.iter_mut() {
+                Rpo256::apply_permutation(state);
+            }
+            self.pos = 0;
+        }
+    }
+}
+
+impl Extend<Felt> for Rpo256State {
+    fn extend<I: IntoIterator<Item = Felt>>(&mut self, elements: I) {
+        for element in elements {
+            self.absorb_element(element);
+        }
+    }
+}
+
+impl Default for Rpo256State {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// INCREMENTAL BYTE HASHER
+// ================================================================================================
+
+/// An incremental RPO sponge, computing the same digest as [Rpo256::hash] over bytes absorbed in
+/// any number of calls.
+///
+/// As [Rpo256::hash], the bytes are absorbed as field elements encoding 7 bytes each. Like
+/// [Rpo256State], this costs twice as many permutations as hashing the bytes at once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Rpo256ByteState {
+    /// The sponge absorbing the field elements encoding the bytes.
+    sponge: Rpo256State,
+
+    /// The bytes which were not absorbed yet since they don't fill up a field element.
+    chunk: [u8; BINARY_CHUNK_SIZE],
+
+    /// The number of bytes in `chunk`.
+    chunk_len: usize,
+
+    /// The total number of bytes absorbed.
+    len: u64,
+}
+
+impl Rpo256ByteState {
+    /// Returns a new sponge which has not absorbed any byte yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Absorbs the provided bytes into the sponge.
+    pub fn absorb(&mut self, mut bytes: &[u8]) {
+        self.len += bytes.len() as u64;
+        while !bytes.is_empty() {
+            let n = (BINARY_CHUNK_SIZE - self.chunk_len).min(bytes.len());
+            self.chunk[self.chunk_len..self.chunk_len + n].copy_from_slice(&bytes[..n]);
+            self.chunk_len += n;
+            bytes = &bytes[n..];
+
+            if self.chunk_len == BINARY_CHUNK_SIZE {
+                self.absorb_chunk();
+            }
+        }
+    }
+
+    /// Returns the digest of all the bytes absorbed by the sponge.
+    pub fn squeeze(mut self) -> RpoDigest {
+        // a partial chunk is separated from the zeros filling up its element by a 1
+        if self.chunk_len != 0 {
+            self.chunk[self.chunk_len..].fill(0);
+            self.chunk[self.chunk_len] = 1;
+            self.absorb_chunk();
+        }
+
+        // as in Rpo256::hash(), the flag depends on whether the number of bytes is a multiple of
+        // the rate
+        let is_rate_multiple = self.len % RATE_WIDTH as u64 == 0;
+        self.sponge.squeeze_with_flag(!is_rate_multiple)
+    }
+
+    // HELPER FUNCTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Absorbs the chunk of bytes as a field element, and empties it.
+    fn absorb_chunk(&mut self) {
+        let mut buf = [0_u8; 8];
+        buf[..BINARY_CHUNK_SIZE].copy_from_slice(&self.chunk);
+        self.sponge.absorb_element(Felt::new(u64::from_le_bytes(buf)));
+        self.chunk_len = 0;
+    }
+}
@@ -3,9 +3,9 @@ use rand_utils::rand_value;
 
 use super::{
     super::{apply_inv_sbox, apply_sbox, ALPHA, INV_ALPHA},
-    Felt, FieldElement, Hasher, Rpo256, RpoDigest, StarkField, ONE, STATE_WIDTH, ZERO,
+    Felt, FieldElement, Hasher, Rpo256, Rpo256State, RpoDigest, StarkField, ONE, STATE_WIDTH, ZERO,
 };
-use crate::Word;
+use crate::{Word, WORD_SIZE};
 use alloc::{collections::BTreeSet, vec::Vec};
 
 #[test]
@@ -75,6 +75,129 @@ fn merge_vs_merge_in_domain() {
     assert_ne!(merge_result, merge_in_domain_result);
 }
 
+#[test]
+fn merge_many() {
+    let digests: Vec<RpoDigest> =
+        (0..9).map(|_| RpoDigest::new([Felt::new(rand_value()); 4])).collect();
+
+    for len in 0..digests.len() {
+        let elements: Vec<Felt> =
+            RpoDigest::digests_as_elements(digests[..len].iter()).copied().collect();
+        assert_eq!(Rpo256::merge_many(&digests[..len]), Rpo256::hash_elements(&elements));
+    }
+    assert_eq!(Rpo256::merge_many(&digests[..2]), Rpo256::merge(&[digests[0], digests[1]]));
+
+    // the digests of the consecutive elements used by the test vectors of `hash_elements`
+    let digests: Vec<RpoDigest> = (0..4)
+        .map(|i| RpoDigest::new(core::array::from_fn(|j| Felt::new(4 * i + j as u64))))
+        .collect();
+    assert_eq!(Rpo256::merge_many(&digests[..3]), RpoDigest::new(EXPECTED[11]));
+    assert_eq!(Rpo256::merge_many(&digests), RpoDigest::new(EXPECTED[15]));
+}
+
+#[test]
+fn hash_many() {
+    let inputs: Vec<[Felt; 8]> =
+        (0..100).map(|_| core::array::from_fn(|_| Felt::new(rand_value()))).collect();
+
+    let digests = Rpo256::hash_many(&inputs);
+    assert_eq!(digests.len(), inputs.len());
+    for (input, digest) in inputs.iter().zip(digests) {
+        assert_eq!(digest, Rpo256::hash_elements(input));
+    }
+    assert!(Rpo256::hash_many::<[Felt; 8]>(&[]).is_empty());
+
+    // inputs of different lengths can be hashed together
+    let inputs: Vec<Vec<Felt>> = (0..20).map(|len| inputs[0][..len % 9].to_vec()).collect();
+    for (input, digest) in inputs.iter().zip(Rpo256::hash_many(&inputs)) {
+        assert_eq!(digest, Rpo256::hash_elements(input));
+    }
+}
+
+#[test]
+fn hash_iter() {
+    let elements: Vec<Felt> = (0..20).map(|_| Felt::new(rand_value())).collect();
+    for len in 0..elements.len() {
+        let expected = Rpo256::hash_elements(&elements[..len]);
+
+        assert_eq!(Rpo256::hash_iter(elements[..len].iter().copied()), expected);
+        assert_eq!(Rpo256::hash_iter(elements[..len].to_vec()), expected);
+    }
+
+    let words: Vec<Word> =
+        elements.chunks_exact(WORD_SIZE).map(|w| w.try_into().unwrap()).collect();
+    for len in 0..words.len() {
+        let expected = Rpo256::hash_elements(&elements[..len * WORD_SIZE]);
+        assert_eq!(Rpo256::hash_words_iter(words[..len].iter().copied()), expected);
+        assert_eq!(Rpo256::hash_words_iter(words[..len].to_vec()), expected);
+    }
+}
+
+#[test]
+fn merge_slice() {
+    let digests: Vec<RpoDigest> =
+        (0..16).map(|_| RpoDigest::new([Felt::new(rand_value()); 4])).collect();
+
+    let parents = Rpo256::merge_slice(&digests);
+    assert_eq!(parents.len(), 8);
+    for (pair, parent) in digests.chunks(2).zip(parents) {
+        assert_eq!(parent, Rpo256::merge(&[pair[0], pair[1]]));
+    }
+    assert!(Rpo256::merge_slice(&[]).is_empty());
+}
+
+#[test]
+fn merge_in_domain_test_vectors() {
+    let digests: [RpoDigest; 2] = [
+        RpoDigest::new([ZERO, ONE, Felt::new(2), Felt::new(3)]),
+        RpoDigest::new([Felt::new(4), Felt::new(5), Felt::new(6), Felt::new(7)]),
+    ];
+
+    let vectors = [
+        (
+            0,
+            [
+                2242391899857912644,
+                12689382052053305418,
+                235236990017815546,
+                5046143039268215739,
+            ],
+        ),
+        (
+            1,
+            [
+                14517227246055557383,
+                8833606318241025707,
+                9537489726871869617,
+                3443285581293744552,
+            ],
+        ),
+        (
+            2,
+            [
+                10807035991859814414,
+                7067887848957672817,
+                13453057192717106249,
+                7739389370097810508,
+            ],
+        ),
+        (
+            1 << 32,
+            [
+                6372154675612333833,
+                12508392331809414058,
+                6906422013149085833,
+                4128254514114954818,
+            ],
+        ),
+    ];
+
+    for (domain, expected) in vectors {
+        let expected = RpoDigest::new(expected.map(Felt::new));
+        assert_eq!(Rpo256::merge_in_domain(&digests, Felt::new(domain)), expected);
+    }
+}
+
 #[test]
 fn hash_elements_vs_merge_with_int() {
     let tmp = [Felt::new(rand_value()); 4];
@@ -135,6 +258,27 @@ fn hash_elements_padding() {
     assert_ne!(r1, r2);
 }
 
+#[test]
+fn incremental_hash_elements() {
+    let elements: Vec<Felt> = (0..50).map(|_| Felt::new(rand_value())).collect();
+
+    // inputs of every length, including multiples of the rate, absorbed in uneven chunks
+    for len in 0..elements.len() {
+        let mut state = Rpo256State::new();
+        for chunk in elements[..len].chunks(3) {
+            state.absorb(chunk);
+        }
+        assert_eq!(state.squeeze(), Rpo256::hash_elements(&elements[..len]));
+    }
+
+    // the same elements absorbed at once or one by one yield the same digest
+    let mut state = Rpo256State::new();
+    state.absorb(&elements);
+    let mut expected = Rpo256State::default();
+    elements.iter().for_each(|element| expected.absorb(&[*element]));
+    assert_eq!(state.squeeze(), expected.squeeze());
+}
+
 #[test]
 fn hash_elements() {
     let elements = [
@@ -1,12 +1,17 @@
 use alloc::string::String;
-use core::{cmp::Ordering, fmt::Display, ops::Deref};
+use core::{
+    cmp::Ordering,
+    fmt::{Display, LowerHex},
+    ops::Deref,
+    str::FromStr,
+};
 
 use super::{Digest, Felt, StarkField, DIGEST_BYTES, DIGEST_SIZE, ZERO};
 use crate::{
     rand::Randomizable,
     utils::{
-        bytes_to_hex_string, hex_to_bytes, ByteReader, ByteWriter, Deserializable,
-        DeserializationError, HexParseError, Serializable,
+        bytes_to_hex_string, ct_eq_bytes, fmt_hex, hex_to_bytes, ByteReader, ByteWriter,
+        Deserializable, DeserializationError, DigestDecodingError, HexParseError, Serializable,
     },
 };
 
@@ -42,6 +47,26 @@ impl RpxDigest {
     pub fn to_hex(&self) -> String {
         bytes_to_hex_string(self.as_bytes())
     }
+
+    /// Returns a digest decoded from the little-endian encodings of its elements, as produced by
+    /// [RpxDigest::as_bytes()].
+    ///
+    /// # Errors
+    /// Returns an error if `bytes` is not exactly [DIGEST_BYTES] long, or if any element is not
+    /// canonically encoded, i.e., it is not smaller than the field modulus.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DigestDecodingError> {
+        bytes.try_into()
+    }
+
+    /// Returns true if this digest is equal to `other`, in time independent of the values of the
+    /// digests.
+    ///
+    /// The derived [PartialEq] returns as soon as an element differs, which is fine for public
+    /// values such as Merkle nodes, but leaks timing information when comparing digests derived
+    /// from secrets, such as message authentication codes.
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        ct_eq_bytes(&self.as_bytes(), &other.as_bytes())
+    }
 }
 
 impl Digest for RpxDigest {
@@ -102,6 +127,14 @@ impl Display for RpxDigest {
     }
 }
 
+impl LowerHex for RpxDigest {
+    /// Writes the 64 hexadecimal digits of the digest, prefixed with `0x` when the alternate flag
+    /// is set (i.e., `{:#x}`).
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        fmt_hex(&self.as_bytes(), f)
+    }
+}
+
 impl Randomizable for RpxDigest {
     const VALUE_SIZE: usize = DIGEST_BYTES;
 
@@ -200,7 +233,7 @@ impl From<[Felt; DIGEST_SIZE]> for RpxDigest {
 }
 
 impl TryFrom<[u8; DIGEST_BYTES]> for RpxDigest {
-    type Error = HexParseError;
+    type Error = DigestDecodingError;
 
     fn try_from(value: [u8; DIGEST_BYTES]) -> Result<Self, Self::Error> {
         // Note: the input length is known, the conversion from slice to array must succeed so the
@@ -211,7 +244,7 @@ impl TryFrom<[u8; DIGEST_BYTES]> for RpxDigest {
         let d = u64::from_le_bytes(value[24..32].try_into().unwrap());
 
         if [a, b, c, d].iter().any(|v| *v >= Felt::MODULUS) {
-            return Err(HexParseError::OutOfRange);
+            return Err(DigestDecodingError::NonCanonicalElement);
         }
 
         Ok(RpxDigest([Felt::new(a), Felt::new(b), Felt::new(c), Felt::new(d)]))
@@ -219,7 +252,7 @@ impl TryFrom<[u8; DIGEST_BYTES]> for RpxDigest {
 }
 
 impl TryFrom<&[u8; DIGEST_BYTES]> for RpxDigest {
-    type Error = HexParseError;
+    type Error = DigestDecodingError;
 
     fn try_from(value: &[u8; DIGEST_BYTES]) -> Result<Self, Self::Error> {
         (*value).try_into()
@@ -227,10 +260,16 @@ impl TryFrom<&[u8; DIGEST_BYTES]> for RpxDigest {
 }
 
 impl TryFrom<&[u8]> for RpxDigest {
-    type Error = HexParseError;
+    type Error = DigestDecodingError;
 
+    /// Expects the slice to be exactly [DIGEST_BYTES] long.
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        (*value).try_into()
+        let bytes: [u8; DIGEST_BYTES] =
+            value.try_into().map_err(|_| DigestDecodingError::InvalidLength {
+                expected: DIGEST_BYTES,
+                actual: value.len(),
+            })?;
+        bytes.try_into()
     }
 }
 
@@ -260,7 +299,17 @@ impl TryFrom<&str> for RpxDigest {
 
     /// Expects the string to start with `0x`.
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        hex_to_bytes(value).and_then(|v| v.try_into())
+        let bytes: [u8; DIGEST_BYTES] = hex_to_bytes(value)?;
+        bytes.try_into().map_err(|_| HexParseError::OutOfRange)
+    }
+}
+
+impl FromStr for RpxDigest {
+    type Err = HexParseError;
+
+    /// Expects the string to start with `0x`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.try_into()
     }
 }
 
@@ -317,7 +366,7 @@ mod tests {
     use rand_utils::rand_value;
 
     use super::{Deserializable, Felt, RpxDigest, Serializable, DIGEST_BYTES, DIGEST_SIZE};
-    use crate::utils::SliceReader;
+    use crate::utils::{HexParseError, SliceReader};
 
     #[test]
     fn digest_serialization() {
@@ -354,6 +403,43 @@ mod tests {
         assert_eq!(digest, round_trip);
     }
 
+    #[test]
+    fn digest_hex_formatting() {
+        let digest =
+            RpxDigest([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(u64::MAX >> 1)]);
+        let hex = "010000000000000002000000000000000300000000000000ffffffffffffff7f";
+        assert_eq!(format!("{digest:x}"), hex);
+        assert_eq!(format!("{digest:#x}"), format!("0x{hex}"));
+        assert_eq!(format!("{digest}"), format!("0x{hex}"));
+        assert_eq!(format!("0x{hex}").parse::<RpxDigest>().unwrap(), digest);
+
+        // parsing is strict about the prefix, the length and the range of the elements
+        assert!(matches!(hex.parse::<RpxDigest>(), Err(HexParseError::InvalidLength { .. })));
+        assert!(matches!(
+            format!("1x{hex}").parse::<RpxDigest>(),
+            Err(HexParseError::MissingPrefix)
+        ));
+        assert!(matches!(
+            format!("0x{}g", &hex[1..]).parse::<RpxDigest>(),
+            Err(HexParseError::InvalidChar)
+        ));
+        assert!(matches!(
+            format!("0x{}", "f".repeat(64)).parse::<RpxDigest>(),
+            Err(HexParseError::OutOfRange)
+        ));
+    }
+
+    #[test]
+    fn digest_ct_eq() {
+        let digest = RpxDigest([Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)]);
+        assert!(digest.ct_eq(&RpxDigest::new(*digest)));
+        for i in 0..DIGEST_SIZE {
+            let mut other = digest;
+            other.0[i] += Felt::new(1);
+            assert!(!digest.ct_eq(&other));
+        }
+    }
+
     #[test]
     fn test_conversions() {
         let digest = RpxDigest([
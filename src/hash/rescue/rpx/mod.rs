@@ -171,38 +171,7 @@ impl ElementHasher for Rpx256 {
     fn hash_elements<E: FieldElement<BaseField = Self::BaseField>>(elements: &[E]) -> Self::Digest {
         // convert the elements into a list of base field elements
         let elements = E::slice_as_base_elements(elements);
-
-        // initialize state to all zeros, except for the first element of the capacity part, which
-        // is set to `elements.len() % RATE_WIDTH`.
-        let mut state = [ZERO; STATE_WIDTH];
-        state[CAPACITY_RANGE.start] = Self::BaseField::from((elements.len() % RATE_WIDTH) as u8);
-
-        // absorb elements into the state one by one until the rate portion of the state is filled
-        // up; then apply the Rescue permutation and start absorbing again; repeat until all
-        // elements have been absorbed
-        let mut i = 0;
-        for &element in elements.iter() {
-            state[RATE_RANGE.start + i] = element;
-            i += 1;
-            if i % RATE_WIDTH == 0 {
-                Self::apply_permutation(&mut state);
-                i = 0;
-            }
-        }
-
-        // if we absorbed some elements but didn't apply a permutation to them (would happen when
-        // the number of elements is not a multiple of RATE_WIDTH), apply the RPX permutation after
-        // padding by as many 0 as necessary to make the input length a multiple of the RATE_WIDTH.
-        if i > 0 {
-            while i != RATE_WIDTH {
-                state[RATE_RANGE.start + i] = ZERO;
-                i += 1;
-            }
-            Self::apply_permutation(&mut state);
-        }
-
-        // return the first 4 elements of the state as hash result
-        RpxDigest::new(state[DIGEST_RANGE].try_into().unwrap())
+        Self::hash_exact_iter(elements.iter().copied(), elements.len())
     }
 }
 
@@ -257,6 +226,23 @@ impl Rpx256 {
         <Self as ElementHasher>::hash_elements(elements)
     }
 
+    /// Returns a hash of the provided field elements prefixed by their number.
+    ///
+    /// [hash_elements()](Rpx256::hash_elements) binds the length of its input only through padding,
+    /// and returns a digest of all ZEROs for an empty input. When variable-length data is committed
+    /// to, this function should be used instead so that the number of elements is bound
+    /// explicitly. The length is counted in base field elements.
+    pub fn hash_elements_with_len<E: FieldElement<BaseField = Felt>>(elements: &[E]) -> RpxDigest {
+        let elements = E::slice_as_base_elements(elements);
+
+        // the length prefix is absorbed directly, without copying the elements after it
+        let len = Felt::new(elements.len() as u64);
+        Self::hash_exact_iter(
+            core::iter::once(len).chain(elements.iter().copied()),
+            elements.len() + 1,
+        )
+    }
+
     // DOMAIN IDENTIFIER
     // --------------------------------------------------------------------------------------------
 
@@ -350,4 +336,46 @@ impl Rpx256 {
         let x3 = x2 * x;
         x3 * x4
     }
+
+    // HELPER FUNCTIONS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a hash of the elements yielded by an iterator known to yield `len` elements, as
+    /// computed by [hash_elements()](Rpx256::hash_elements).
+    ///
+    /// # Panics
+    /// Panics if the iterator yields a different number of elements than `len`.
+    fn hash_exact_iter(elements: impl Iterator<Item = Felt>, len: usize) -> RpxDigest {
+        // initialize state to all zeros, except for the first element of the capacity part, which
+        // is set to `len % RATE_WIDTH`.
+        let mut state = [ZERO; STATE_WIDTH];
+        state[CAPACITY_RANGE.start] = Felt::from((len % RATE_WIDTH) as u8);
+
+        // absorb elements into the state one by one until the rate portion of the state is filled
+        // up; then apply the Rescue permutation and start absorbing again; repeat until all
+        // elements have been absorbed
+        let mut i = 0;
+        let mut count = 0;
+        for element in elements {
+            state[RATE_RANGE.start + i] = element;
+            count += 1;
+            i += 1;
+            if i == RATE_WIDTH {
+                Self::apply_permutation(&mut state);
+                i = 0;
+            }
+        }
+        assert_eq!(count, len, "iterator yielded a different number of elements than expected");
+
+        // if we absorbed some elements but didn't apply a permutation to them (would happen when
+        // the number of elements is not a multiple of RATE_WIDTH), apply the RPX permutation after
+        // padding by as many 0 as necessary to make the input length a multiple of the RATE_WIDTH.
+        if i > 0 {
+            state[RATE_RANGE.start + i..RATE_RANGE.end].fill(ZERO);
+            Self::apply_permutation(&mut state);
+        }
+
+        // return the first 4 elements of the state as hash result
+        RpxDigest::new(state[DIGEST_RANGE].try_into().unwrap())
+    }
 }
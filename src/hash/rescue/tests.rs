@@ -1,6 +1,11 @@
 use rand_utils::rand_value;
 
-use super::{Felt, FieldElement, ALPHA, INV_ALPHA};
+use super::{
+    add_constants, add_constants_and_apply_inv_sbox, add_constants_and_apply_sbox, apply_inv_sbox,
+    apply_sbox, Felt, FieldElement, Rpo256, RpoDigest, Rpx256, RpxDigest, ALPHA, ARK1, ARK2,
+    INV_ALPHA, STATE_WIDTH, ZERO,
+};
+use alloc::vec::Vec;
 
 #[test]
 fn test_alphas() {
@@ -8,3 +13,58 @@ fn test_alphas() {
     let e_exp = e.exp(ALPHA);
     assert_eq!(e, e_exp.exp(INV_ALPHA));
 }
+
+#[test]
+fn test_optimized_sbox_matches_scalar() {
+    for round in 0..ARK1.len() {
+        let state: [Felt; STATE_WIDTH] = core::array::from_fn(|_| Felt::new(rand_value()));
+
+        // the optimized functions return false when no accelerated implementation is available
+        let mut expected = state;
+        add_constants(&mut expected, &ARK1[round]);
+        apply_sbox(&mut expected);
+        let mut actual = state;
+        if add_constants_and_apply_sbox(&mut actual, &ARK1[round]) {
+            assert_eq!(expected, actual);
+        }
+
+        let mut expected = state;
+        add_constants(&mut expected, &ARK2[round]);
+        apply_inv_sbox(&mut expected);
+        let mut actual = state;
+        if add_constants_and_apply_inv_sbox(&mut actual, &ARK2[round]) {
+            assert_eq!(expected, actual);
+        }
+    }
+}
+
+#[test]
+fn test_hash_elements_with_len() {
+    let elements: Vec<Felt> = (0..20).map(|_| Felt::new(rand_value())).collect();
+    for len in 0..elements.len() {
+        let prefixed = [&[Felt::new(len as u64)][..], &elements[..len]].concat();
+        assert_eq!(
+            Rpo256::hash_elements_with_len(&elements[..len]),
+            Rpo256::hash_elements(&prefixed)
+        );
+        assert_eq!(
+            Rpx256::hash_elements_with_len(&elements[..len]),
+            Rpx256::hash_elements(&prefixed)
+        );
+    }
+
+    // trailing zeros and the empty input are bound by the length prefix
+    let padded = [elements[0], elements[1], ZERO];
+    assert_ne!(
+        Rpo256::hash_elements_with_len(&elements[..2]),
+        Rpo256::hash_elements_with_len(&padded)
+    );
+    assert_ne!(
+        Rpx256::hash_elements_with_len(&elements[..2]),
+        Rpx256::hash_elements_with_len(&padded)
+    );
+    assert_eq!(Rpo256::hash_elements::<Felt>(&[]), RpoDigest::default());
+    assert_ne!(Rpo256::hash_elements_with_len::<Felt>(&[]), RpoDigest::default());
+    assert_eq!(Rpx256::hash_elements::<Felt>(&[]), RpxDigest::default());
+    assert_ne!(Rpx256::hash_elements_with_len::<Felt>(&[]), RpxDigest::default());
+}
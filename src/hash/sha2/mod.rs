@@ -0,0 +1,22 @@
+use ::sha2::Digest as _;
+
+use super::byte_digest::{byte_digest, byte_hasher};
+
+#[cfg(test)]
+mod tests;
+
+// SHA256 DIGEST
+// ================================================================================================
+
+byte_digest! {
+    /// 32-bytes output of the SHA-256 hash function.
+    Sha256Digest
+}
+
+// SHA256 HASHER
+// ================================================================================================
+
+byte_hasher! {
+    /// SHA-256 hasher, as standardized in FIPS 180-4.
+    Sha256, Sha256Digest, ::sha2::Sha256
+}
@@ -0,0 +1,16 @@
+use super::*;
+
+#[test]
+fn sha256_test_vectors() {
+    let vectors = [
+        ("", "0xe3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
+        ("abc", "0xba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+    ];
+
+    for (input, expected) in vectors {
+        let digest = Sha256::hash(input.as_bytes());
+        assert_eq!(digest, expected.parse().unwrap());
+        assert_eq!(format!("{digest}"), expected);
+        assert_eq!(format!("{digest:x}"), expected[2..]);
+    }
+}
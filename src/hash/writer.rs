@@ -0,0 +1,179 @@
+use super::{
+    blake::{Blake3Digest, Blake3_256},
+    keccak::{Keccak256, Keccak256Digest},
+    rpo::{Rpo256, Rpo256ByteState, RpoDigest},
+    sha2::{Sha256, Sha256Digest},
+    Hasher,
+};
+use sha3::Digest as _;
+
+// INCREMENTAL HASHER
+// ================================================================================================
+
+/// A hasher whose digests of byte sequences can be computed incrementally, over bytes provided in
+/// any number of calls.
+///
+/// For every such hasher, the digest of the concatenation of all the provided bytes is the same as
+/// the one computed by [Hasher::hash].
+pub trait IncrementalHasher: Hasher {
+    /// The state of an incremental computation of a digest.
+    type State: Default;
+
+    /// Updates the state with the provided bytes.
+    fn update(state: &mut Self::State, bytes: &[u8]);
+
+    /// Returns the digest of all the bytes with which the state was updated.
+    fn finalize(state: Self::State) -> Self::Digest;
+}
+
+impl IncrementalHasher for Rpo256 {
+    type State = Rpo256ByteState;
+
+    fn update(state: &mut Self::State, bytes: &[u8]) {
+        state.absorb(bytes);
+    }
+
+    fn finalize(state: Self::State) -> RpoDigest {
+        state.squeeze()
+    }
+}
+
+impl IncrementalHasher for Blake3_256 {
+    type State = blake3::Hasher;
+
+    fn update(state: &mut Self::State, bytes: &[u8]) {
+        state.update(bytes);
+    }
+
+    fn finalize(state: Self::State) -> Blake3Digest<32> {
+        <[u8; 32]>::from(state.finalize()).into()
+    }
+}
+
+impl IncrementalHasher for Keccak256 {
+    type State = sha3::Keccak256;
+
+    fn update(state: &mut Self::State, bytes: &[u8]) {
+        state.update(bytes);
+    }
+
+    fn finalize(state: Self::State) -> Keccak256Digest {
+        <[u8; 32]>::from(state.finalize()).into()
+    }
+}
+
+impl IncrementalHasher for Sha256 {
+    type State = ::sha2::Sha256;
+
+    fn update(state: &mut Self::State, bytes: &[u8]) {
+        state.update(bytes);
+    }
+
+    fn finalize(state: Self::State) -> Sha256Digest {
+        <[u8; 32]>::from(state.finalize()).into()
+    }
+}
+
+// HASHER WRITER
+// ================================================================================================
+
+/// An adapter computing the digest of the bytes written into it, without buffering them.
+///
+/// With the `std` feature, this implements [std::io::Write], so that anything which can be
+/// written into a writer, e.g., by a serializer or an encoder, can be hashed directly, without
+/// building an intermediate buffer.
+pub struct HasherWriter<H: IncrementalHasher> {
+    state: H::State,
+}
+
+impl<H: IncrementalHasher> HasherWriter<H> {
+    /// Returns a new writer into which no bytes were written yet.
+    pub fn new() -> Self {
+        Self { state: H::State::default() }
+    }
+
+    /// Feeds the provided bytes into the hasher.
+    pub fn update(&mut self, bytes: &[u8]) {
+        H::update(&mut self.state, bytes);
+    }
+
+    /// Returns the digest of all the bytes written into this writer, which is the same as the
+    /// result of [Hasher::hash] over their concatenation.
+    pub fn finalize(self) -> H::Digest {
+        H::finalize(self.state)
+    }
+}
+
+impl<H: IncrementalHasher> Default for HasherWriter<H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: IncrementalHasher> core::fmt::Debug for HasherWriter<H> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("HasherWriter").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<H: IncrementalHasher> std::io::Write for HasherWriter<H> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{Blake3_256, Hasher, HasherWriter, IncrementalHasher, Keccak256, Rpo256, Sha256};
+    use alloc::vec::Vec;
+    use rand_utils::rand_vector;
+
+    /// Checks that writing the bytes in chunks of any size produces the same digest as hashing
+    /// them at once.
+    fn check_incremental_hashing<H: IncrementalHasher>() {
+        let bytes = rand_vector::<u8>(200);
+        for len in [0, 1, 6, 7, 8, 55, 56, 57, 63, 64, 65, 200] {
+            let expected = H::hash(&bytes[..len]);
+            for chunk_size in [1, 3, 7, 8, 64] {
+                let mut writer = HasherWriter::<H>::new();
+                for chunk in bytes[..len].chunks(chunk_size) {
+                    writer.update(chunk);
+                }
+                assert_eq!(writer.finalize(), expected, "len {len}, chunk size {chunk_size}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_incremental_hashing() {
+        check_incremental_hashing::<Rpo256>();
+        check_incremental_hashing::<Blake3_256>();
+        check_incremental_hashing::<Keccak256>();
+        check_incremental_hashing::<Sha256>();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_hasher_writer() {
+        use std::io::Write;
+
+        let bytes: Vec<u8> = (0..100).collect();
+        let mut writer = HasherWriter::<Rpo256>::new();
+        writer.write_all(&bytes[..30]).unwrap();
+        write!(writer, "{}", bytes.len()).unwrap();
+        writer.flush().unwrap();
+
+        let mut expected = bytes[..30].to_vec();
+        expected.extend_from_slice(b"100");
+        assert_eq!(writer.finalize(), Rpo256::hash(&expected));
+    }
+}
@@ -9,6 +9,7 @@ extern crate std;
 pub mod dsa;
 pub mod hash;
 pub mod merkle;
+pub mod prf;
 pub mod rand;
 pub mod utils;
 
@@ -1,6 +1,6 @@
 use core::slice;
 
-use super::{Felt, RpoDigest, EMPTY_WORD};
+use super::{Felt, RpoDigest, EMPTY_WORD, MAX_EMPTY_SUBTREE_DEPTH};
 
 // EMPTY NODES SUBTREES
 // ================================================================================================
@@ -12,7 +12,8 @@ impl EmptySubtreeRoots {
     /// Returns a static slice with roots of empty subtrees of a Merkle tree starting at the
     /// specified depth.
     pub const fn empty_hashes(tree_depth: u8) -> &'static [RpoDigest] {
-        let ptr = &EMPTY_SUBTREES[255 - tree_depth as usize] as *const RpoDigest;
+        let ptr = &EMPTY_SUBTREES[MAX_EMPTY_SUBTREE_DEPTH as usize - tree_depth as usize]
+            as *const RpoDigest;
         // Safety: this is a static/constant array, so it will never be outlived. If we attempt to
         // use regular slices, this wouldn't be a `const` function, meaning we won't be able to use
         // the returned value for static/constant definitions.
@@ -22,12 +23,12 @@ impl EmptySubtreeRoots {
     /// Returns the node's digest for a sub-tree with all its leaves set to the empty word.
     pub const fn entry(tree_depth: u8, node_depth: u8) -> &'static RpoDigest {
         assert!(node_depth <= tree_depth);
-        let pos = 255 - tree_depth + node_depth;
+        let pos = MAX_EMPTY_SUBTREE_DEPTH - tree_depth + node_depth;
         &EMPTY_SUBTREES[pos as usize]
     }
 }
 
-const EMPTY_SUBTREES: [RpoDigest; 256] = [
+const EMPTY_SUBTREES: [RpoDigest; MAX_EMPTY_SUBTREE_DEPTH as usize + 1] = [
     RpoDigest::new([
         Felt::new(0xee3d94db86d48dc4),
         Felt::new(0x3d13166c7aba0368),
@@ -0,0 +1,469 @@
+use alloc::{string::ToString, vec::Vec};
+use core::fmt;
+
+use super::{MerklePath, MultiProof, RpoDigest, SmtProof, MAX_TREE_DEPTH, SMT_DEPTH};
+use crate::utils::{
+    ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable, SliceReader,
+};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Version of the [ProofEnvelope] wire format.
+pub const PROOF_ENVELOPE_VERSION: u8 = 1;
+
+// PROOF KIND
+// ================================================================================================
+
+/// Type of the proof carried by a [ProofEnvelope].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[repr(u8)]
+pub enum ProofKind {
+    /// A [MerklePath] opening a single node of a Merkle tree.
+    MerklePath = 0,
+    /// An [SmtProof] opening a leaf of an [Smt](super::Smt).
+    Smt = 1,
+    /// A [MultiProof] opening several nodes of a Merkle tree.
+    Multi = 2,
+}
+
+impl TryFrom<u8> for ProofKind {
+    type Error = ProofEnvelopeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::MerklePath),
+            1 => Ok(Self::Smt),
+            2 => Ok(Self::Multi),
+            _ => Err(ProofEnvelopeError::UnknownProofKind(value)),
+        }
+    }
+}
+
+// HASHER KIND
+// ================================================================================================
+
+/// Hash function used to build the tree a [ProofEnvelope] commits to.
+///
+/// All Merkle data structures in this crate are currently built with RPO; the tag is part of the
+/// wire format so that proofs over other hash functions can be added without breaking it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[repr(u8)]
+pub enum HasherKind {
+    /// Rescue Prime Optimized hash function with 256-bit output.
+    Rpo256 = 0,
+}
+
+impl TryFrom<u8> for HasherKind {
+    type Error = ProofEnvelopeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Self::Rpo256),
+            _ => Err(ProofEnvelopeError::UnknownHasher(value)),
+        }
+    }
+}
+
+// ENVELOPE PROOF
+// ================================================================================================
+
+/// A proof which can be carried by a [ProofEnvelope].
+pub trait EnvelopeProof: Serializable + Deserializable {
+    /// The tag identifying this type of proof in an envelope.
+    const KIND: ProofKind;
+
+    /// Returns true if this proof is consistent with a tree of the specified depth.
+    fn is_valid_for_depth(&self, depth: u8) -> bool;
+}
+
+impl EnvelopeProof for MerklePath {
+    const KIND: ProofKind = ProofKind::MerklePath;
+
+    fn is_valid_for_depth(&self, depth: u8) -> bool {
+        self.len() == depth as usize
+    }
+}
+
+impl EnvelopeProof for SmtProof {
+    const KIND: ProofKind = ProofKind::Smt;
+
+    fn is_valid_for_depth(&self, depth: u8) -> bool {
+        // the length of the path is checked when the proof is constructed
+        depth == SMT_DEPTH
+    }
+}
+
+impl EnvelopeProof for MultiProof {
+    const KIND: ProofKind = ProofKind::Multi;
+
+    fn is_valid_for_depth(&self, depth: u8) -> bool {
+        self.nodes().all(|(index, _)| index.depth() <= depth)
+    }
+}
+
+// PROOF ENVELOPE
+// ================================================================================================
+
+/// A self-describing container for a serialized proof.
+///
+/// Alongside the proof itself, the envelope carries the type of the proof, the hash function and
+/// depth of the tree it was produced from, and the root it opens to. This allows a receiver to
+/// route and verify proofs without any out-of-band context.
+///
+/// The wire format of an envelope is:
+/// - the format version, as a single byte;
+/// - the [ProofKind] tag, as a single byte;
+/// - the [HasherKind] tag, as a single byte;
+/// - the depth of the tree, as a single byte;
+/// - the root of the tree;
+/// - the length-prefixed serialized proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "ProofEnvelopeParts"))]
+pub struct ProofEnvelope {
+    kind: ProofKind,
+    hasher: HasherKind,
+    depth: u8,
+    root: RpoDigest,
+    payload: Vec<u8>,
+}
+
+impl ProofEnvelope {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [ProofEnvelope] wrapping the specified proof against `root` of an RPO tree of
+    /// the specified depth.
+    ///
+    /// # Errors
+    /// Returns an error if the depth is zero or greater than [MAX_TREE_DEPTH], or if the proof is
+    /// inconsistent with the depth.
+    pub fn new<P: EnvelopeProof>(
+        proof: &P,
+        depth: u8,
+        root: RpoDigest,
+    ) -> Result<Self, ProofEnvelopeError> {
+        check_depth(depth)?;
+        if !proof.is_valid_for_depth(depth) {
+            return Err(ProofEnvelopeError::ProofInconsistentWithDepth(depth));
+        }
+
+        Ok(Self {
+            kind: P::KIND,
+            hasher: HasherKind::Rpo256,
+            depth,
+            root,
+            payload: proof.to_bytes(),
+        })
+    }
+
+    /// Parses a [ProofEnvelope] from the provided bytes.
+    ///
+    /// # Errors
+    /// Returns an error if the bytes are not a valid encoding of an envelope, or if they contain
+    /// data beyond the end of the envelope.
+    pub fn parse(bytes: &[u8]) -> Result<Self, ProofEnvelopeError> {
+        let mut source = SliceReader::new(bytes);
+        let envelope = Self::read_checked(&mut source)?;
+        if source.has_more_bytes() {
+            return Err(ProofEnvelopeError::TrailingBytes);
+        }
+
+        Ok(envelope)
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the type of the proof in this envelope.
+    pub fn kind(&self) -> ProofKind {
+        self.kind
+    }
+
+    /// Returns the hash function of the tree this envelope commits to.
+    pub fn hasher(&self) -> HasherKind {
+        self.hasher
+    }
+
+    /// Returns the depth of the tree this envelope commits to.
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Returns the root of the tree this envelope commits to.
+    pub fn root(&self) -> RpoDigest {
+        self.root
+    }
+
+    /// Returns the serialized proof carried by this envelope.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Decodes the proof carried by this envelope.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - The envelope carries a different type of proof.
+    /// - The payload is not a valid encoding of the proof, or contains trailing data.
+    /// - The decoded proof is inconsistent with the depth of the envelope.
+    pub fn open<P: EnvelopeProof>(&self) -> Result<P, ProofEnvelopeError> {
+        if self.kind != P::KIND {
+            return Err(ProofEnvelopeError::ProofKindMismatch {
+                expected: P::KIND,
+                found: self.kind,
+            });
+        }
+
+        let mut source = SliceReader::new(&self.payload);
+        let proof = P::read_from(&mut source).map_err(ProofEnvelopeError::InvalidPayload)?;
+        if source.has_more_bytes() {
+            return Err(ProofEnvelopeError::TrailingBytes);
+        }
+        if !proof.is_valid_for_depth(self.depth) {
+            return Err(ProofEnvelopeError::ProofInconsistentWithDepth(self.depth));
+        }
+
+        Ok(proof)
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Reads an envelope from the source, validating each of its headers.
+    fn read_checked<R: ByteReader>(source: &mut R) -> Result<Self, ProofEnvelopeError> {
+        let version = source.read_u8()?;
+        if version != PROOF_ENVELOPE_VERSION {
+            return Err(ProofEnvelopeError::UnsupportedVersion(version));
+        }
+
+        let kind = ProofKind::try_from(source.read_u8()?)?;
+        let hasher = HasherKind::try_from(source.read_u8()?)?;
+        let depth = source.read_u8()?;
+        check_depth(depth)?;
+        let root = RpoDigest::read_from(source)?;
+        let payload_len = source.read_usize()?;
+        let payload = source.read_vec(payload_len)?;
+
+        Ok(Self { kind, hasher, depth, root, payload })
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+fn check_depth(depth: u8) -> Result<(), ProofEnvelopeError> {
+    if depth == 0 || depth > MAX_TREE_DEPTH {
+        return Err(ProofEnvelopeError::InvalidDepth(depth));
+    }
+    Ok(())
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+/// The serialized fields of a [ProofEnvelope], whose headers are validated on deserialization as
+/// in [ProofEnvelope::parse].
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct ProofEnvelopeParts {
+    kind: ProofKind,
+    hasher: HasherKind,
+    depth: u8,
+    root: RpoDigest,
+    payload: Vec<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<ProofEnvelopeParts> for ProofEnvelope {
+    type Error = ProofEnvelopeError;
+
+    fn try_from(parts: ProofEnvelopeParts) -> Result<Self, Self::Error> {
+        // unknown proof kind and hasher tags are rejected when deserializing the enums
+        check_depth(parts.depth)?;
+
+        let ProofEnvelopeParts { kind, hasher, depth, root, payload } = parts;
+        Ok(Self { kind, hasher, depth, root, payload })
+    }
+}
+
+impl Serializable for ProofEnvelope {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(PROOF_ENVELOPE_VERSION);
+        target.write_u8(self.kind as u8);
+        target.write_u8(self.hasher as u8);
+        target.write_u8(self.depth);
+        self.root.write_into(target);
+        target.write_usize(self.payload.len());
+        target.write_bytes(&self.payload);
+    }
+}
+
+impl Deserializable for ProofEnvelope {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Self::read_checked(source).map_err(|err| match err {
+            ProofEnvelopeError::InvalidEncoding(err) => err,
+            err => DeserializationError::InvalidValue(err.to_string()),
+        })
+    }
+}
+
+// PROOF ENVELOPE ERROR
+// ================================================================================================
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ProofEnvelopeError {
+    InvalidDepth(u8),
+    InvalidEncoding(DeserializationError),
+    InvalidPayload(DeserializationError),
+    ProofInconsistentWithDepth(u8),
+    ProofKindMismatch {
+        expected: ProofKind,
+        found: ProofKind,
+    },
+    TrailingBytes,
+    UnknownHasher(u8),
+    UnknownProofKind(u8),
+    UnsupportedVersion(u8),
+}
+
+impl From<DeserializationError> for ProofEnvelopeError {
+    fn from(err: DeserializationError) -> Self {
+        Self::InvalidEncoding(err)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ProofEnvelopeError {}
+
+impl fmt::Display for ProofEnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ProofEnvelopeError::*;
+        match self {
+            InvalidDepth(depth) => {
+                write!(f, "the depth {depth} is not in the range 1..={MAX_TREE_DEPTH}")
+            }
+            InvalidEncoding(err) => write!(f, "the envelope encoding is invalid: {err}"),
+            InvalidPayload(err) => write!(f, "the proof encoding is invalid: {err}"),
+            ProofInconsistentWithDepth(depth) => {
+                write!(f, "the proof is inconsistent with a tree of depth {depth}")
+            }
+            ProofKindMismatch { expected, found } => {
+                write!(f, "expected a proof of type {expected:?}, but the envelope holds {found:?}")
+            }
+            TrailingBytes => write!(f, "unexpected bytes after the end of the encoding"),
+            UnknownHasher(tag) => write!(f, "the hasher tag {tag} is unknown"),
+            UnknownProofKind(tag) => write!(f, "the proof kind tag {tag} is unknown"),
+            UnsupportedVersion(version) => {
+                write!(f, "the envelope version {version} is not supported")
+            }
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        EnvelopeProof, MerklePath, ProofEnvelope, ProofEnvelopeError, ProofKind, RpoDigest,
+        Serializable, SmtProof,
+    };
+    use crate::merkle::{digests_to_words, int_to_node, MerkleTree, NodeIndex};
+
+    const LEAVES4: [RpoDigest; 4] =
+        [int_to_node(1), int_to_node(2), int_to_node(3), int_to_node(4)];
+
+    #[test]
+    fn test_envelope_roundtrip() {
+        let tree = MerkleTree::new(digests_to_words(&LEAVES4)).unwrap();
+        let path = tree.get_path(NodeIndex::new(2, 1).unwrap()).unwrap();
+
+        let envelope = ProofEnvelope::new(&path, 2, tree.root()).unwrap();
+        let parsed = ProofEnvelope::parse(&envelope.to_bytes()).unwrap();
+        assert_eq!(parsed, envelope);
+        assert_eq!(parsed.kind(), MerklePath::KIND);
+        assert_eq!(parsed.depth(), 2);
+        assert_eq!(parsed.root(), tree.root());
+
+        let opened: MerklePath = parsed.open().unwrap();
+        assert_eq!(opened, path);
+        assert!(opened.verify(1, LEAVES4[1], &parsed.root()));
+    }
+
+    #[test]
+    fn test_envelope_strict_parsing() {
+        let tree = MerkleTree::new(digests_to_words(&LEAVES4)).unwrap();
+        let path = tree.get_path(NodeIndex::new(2, 1).unwrap()).unwrap();
+        let bytes = ProofEnvelope::new(&path, 2, tree.root()).unwrap().to_bytes();
+
+        let mut invalid = bytes.clone();
+        invalid[0] = 2;
+        assert_eq!(ProofEnvelope::parse(&invalid), Err(ProofEnvelopeError::UnsupportedVersion(2)));
+
+        let mut invalid = bytes.clone();
+        invalid[1] = 7;
+        assert_eq!(ProofEnvelope::parse(&invalid), Err(ProofEnvelopeError::UnknownProofKind(7)));
+
+        let mut invalid = bytes.clone();
+        invalid[2] = 7;
+        assert_eq!(ProofEnvelope::parse(&invalid), Err(ProofEnvelopeError::UnknownHasher(7)));
+
+        let mut invalid = bytes.clone();
+        invalid[3] = 65;
+        assert_eq!(ProofEnvelope::parse(&invalid), Err(ProofEnvelopeError::InvalidDepth(65)));
+
+        let mut invalid = bytes.clone();
+        invalid.push(0);
+        assert_eq!(ProofEnvelope::parse(&invalid), Err(ProofEnvelopeError::TrailingBytes));
+
+        assert!(matches!(
+            ProofEnvelope::parse(&bytes[..bytes.len() - 1]),
+            Err(ProofEnvelopeError::InvalidEncoding(_))
+        ));
+    }
+
+    #[test]
+    fn test_envelope_rejects_mismatched_proofs() {
+        let tree = MerkleTree::new(digests_to_words(&LEAVES4)).unwrap();
+        let path = tree.get_path(NodeIndex::new(2, 1).unwrap()).unwrap();
+
+        assert_eq!(
+            ProofEnvelope::new(&path, 3, tree.root()),
+            Err(ProofEnvelopeError::ProofInconsistentWithDepth(3))
+        );
+
+        let envelope = ProofEnvelope::new(&path, 2, tree.root()).unwrap();
+        assert_eq!(
+            envelope.open::<SmtProof>(),
+            Err(ProofEnvelopeError::ProofKindMismatch {
+                expected: ProofKind::Smt,
+                found: ProofKind::MerklePath,
+            })
+        );
+    }
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_envelope_deserialization_is_validated() {
+        use super::{HasherKind, ProofEnvelopeParts};
+
+        let tree = MerkleTree::new(digests_to_words(&LEAVES4)).unwrap();
+        let path = tree.get_path(NodeIndex::new(2, 1).unwrap()).unwrap();
+        let envelope = ProofEnvelope::new(&path, 2, tree.root()).unwrap();
+
+        let parts = |depth| ProofEnvelopeParts {
+            kind: ProofKind::MerklePath,
+            hasher: HasherKind::Rpo256,
+            depth,
+            root: tree.root(),
+            payload: path.to_bytes(),
+        };
+        assert_eq!(ProofEnvelope::try_from(parts(2)), Ok(envelope));
+        assert_eq!(ProofEnvelope::try_from(parts(0)), Err(ProofEnvelopeError::InvalidDepth(0)));
+        assert_eq!(ProofEnvelope::try_from(parts(65)), Err(ProofEnvelopeError::InvalidDepth(65)));
+    }
+}
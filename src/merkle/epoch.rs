@@ -0,0 +1,307 @@
+use alloc::vec::Vec;
+
+use super::{
+    LeafIndex, MerkleError, MerklePath, Mmr, MmrError, MmrPeaks, MmrProof, Rpo256, RpoDigest,
+    SimpleSmt, Word,
+};
+
+// EPOCH ACCUMULATOR
+// ================================================================================================
+
+/// An append-only accumulator whose values are grouped into epochs.
+///
+/// Values are appended to the tree of the current epoch, which is a [SimpleSmt] of depth `DEPTH`
+/// filled from left to right. When an epoch is sealed, the root of its tree is added to an [Mmr]
+/// of epoch roots (the frontier) and a new, empty tree is started for the next epoch.
+///
+/// The accumulator as a whole is committed to by merging the root of the current epoch with the
+/// hash of the frontier peaks. Every value ever appended can be proven with an [EpochProof]:
+/// values of the current epoch are opened against the root of the current epoch, and values of
+/// sealed epochs are opened against the root of their epoch, which in turn is opened against the
+/// frontier.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct EpochAccumulator<const DEPTH: u8> {
+    current: SimpleSmt<DEPTH>,
+    num_values: u64,
+    sealed: Vec<(SimpleSmt<DEPTH>, u64)>,
+    frontier: Mmr,
+}
+
+impl<const DEPTH: u8> EpochAccumulator<DEPTH> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [EpochAccumulator] with no values and no sealed epochs.
+    ///
+    /// # Errors
+    /// Returns an error if DEPTH is 0 or is greater than 63.
+    pub fn new() -> Result<Self, MmrError> {
+        if DEPTH > 63 {
+            return Err(MmrError::MerkleError(MerkleError::DepthTooBig(DEPTH as u64)));
+        }
+
+        Ok(Self {
+            current: SimpleSmt::new().map_err(MmrError::MerkleError)?,
+            num_values: 0,
+            sealed: Vec::new(),
+            frontier: Mmr::new(),
+        })
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of the current epoch, which is also the number of sealed epochs.
+    pub fn epoch(&self) -> usize {
+        self.sealed.len()
+    }
+
+    /// Returns the maximum number of values in a single epoch.
+    pub const fn epoch_capacity(&self) -> u64 {
+        1 << DEPTH
+    }
+
+    /// Returns the number of values appended during the current epoch.
+    pub fn num_values(&self) -> u64 {
+        self.num_values
+    }
+
+    /// Returns the root of the tree of the current epoch.
+    pub fn current_root(&self) -> RpoDigest {
+        self.current.root()
+    }
+
+    /// Returns the peaks of the MMR of sealed epoch roots.
+    pub fn frontier(&self) -> MmrPeaks {
+        self.frontier
+            .peaks(self.frontier.forest())
+            .expect("current forest is always valid")
+    }
+
+    /// Returns the commitment to the whole accumulator, computed as the merge of the root of the
+    /// current epoch with the hash of the frontier peaks.
+    pub fn commitment(&self) -> RpoDigest {
+        Rpo256::merge(&[self.current_root(), self.frontier().hash_peaks()])
+    }
+
+    /// Returns the value at the specified position of the specified epoch.
+    ///
+    /// # Errors
+    /// Returns an error if the epoch is not the current or a sealed epoch, or if no value was
+    /// appended at the specified position of that epoch.
+    pub fn get(&self, epoch: usize, index: u64) -> Result<Word, MmrError> {
+        let (tree, num_values) = self.epoch_tree(epoch)?;
+        Ok(tree.get_leaf(&Self::leaf_index(index, num_values)?))
+    }
+
+    /// Returns a proof of the value at the specified position of the specified epoch.
+    ///
+    /// # Errors
+    /// Returns an error if the epoch is not the current or a sealed epoch, or if no value was
+    /// appended at the specified position of that epoch.
+    pub fn open(&self, epoch: usize, index: u64) -> Result<EpochProof<DEPTH>, MmrError> {
+        let (tree, num_values) = self.epoch_tree(epoch)?;
+        let path = tree.open(&Self::leaf_index(index, num_values)?).path;
+
+        let epoch_opening = if epoch < self.epoch() {
+            Some(self.frontier.open(epoch, self.frontier.forest())?)
+        } else {
+            None
+        };
+
+        Ok(EpochProof { epoch, index, path, epoch_opening })
+    }
+
+    // STATE MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Appends a value to the current epoch and returns its position within the epoch.
+    ///
+    /// # Errors
+    /// Returns an error if the current epoch is full.
+    pub fn append(&mut self, value: Word) -> Result<u64, MmrError> {
+        let index = self.num_values;
+        if index == self.epoch_capacity() {
+            let err = MerkleError::InvalidNumEntries(self.epoch_capacity() as usize);
+            return Err(MmrError::MerkleError(err));
+        }
+
+        self.current
+            .insert(LeafIndex::new(index).map_err(MmrError::MerkleError)?, value);
+        self.num_values += 1;
+
+        Ok(index)
+    }
+
+    /// Seals the current epoch and starts a new, empty one.
+    ///
+    /// The root of the sealed epoch is added to the frontier and returned.
+    pub fn seal_epoch(&mut self) -> RpoDigest {
+        let empty = SimpleSmt::new().expect("depth was validated on construction");
+        let sealed = core::mem::replace(&mut self.current, empty);
+        let root = sealed.root();
+
+        self.frontier.add(root);
+        self.sealed.push((sealed, self.num_values));
+        self.num_values = 0;
+
+        root
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the tree of the specified epoch, together with the number of values it holds.
+    fn epoch_tree(&self, epoch: usize) -> Result<(&SimpleSmt<DEPTH>, u64), MmrError> {
+        match epoch.cmp(&self.epoch()) {
+            core::cmp::Ordering::Less => {
+                let (tree, num_values) = &self.sealed[epoch];
+                Ok((tree, *num_values))
+            }
+            core::cmp::Ordering::Equal => Ok((&self.current, self.num_values)),
+            core::cmp::Ordering::Greater => Err(MmrError::InvalidPosition(epoch)),
+        }
+    }
+
+    /// Returns the leaf index of the value at the specified position of an epoch which holds
+    /// `num_values` values.
+    fn leaf_index(index: u64, num_values: u64) -> Result<LeafIndex<DEPTH>, MmrError> {
+        if index >= num_values {
+            let err = MerkleError::InvalidIndex { depth: DEPTH, value: index };
+            return Err(MmrError::MerkleError(err));
+        }
+        LeafIndex::new(index).map_err(MmrError::MerkleError)
+    }
+}
+
+// EPOCH PROOF
+// ================================================================================================
+
+/// A proof that a value was appended to an [EpochAccumulator] of depth `DEPTH` at a given position
+/// of a given epoch.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct EpochProof<const DEPTH: u8> {
+    /// The epoch the value was appended in.
+    pub epoch: usize,
+
+    /// The position of the value within its epoch.
+    pub index: u64,
+
+    /// The Merkle path from the value to the root of its epoch.
+    pub path: MerklePath,
+
+    /// The opening of the epoch root against the frontier, present only for sealed epochs.
+    pub epoch_opening: Option<MmrProof>,
+}
+
+impl<const DEPTH: u8> EpochProof<DEPTH> {
+    /// Returns true if this proof opens `value` against an accumulator with the specified current
+    /// epoch root and frontier.
+    ///
+    /// The path must lead from a leaf of an epoch tree, i.e., be exactly `DEPTH` long, and the
+    /// epoch must be the current one if the proof opens the value against the current epoch root,
+    /// or a sealed one otherwise.
+    pub fn verify(&self, value: Word, current_root: RpoDigest, frontier: &MmrPeaks) -> bool {
+        if self.path.depth() != DEPTH {
+            return false;
+        }
+
+        match &self.epoch_opening {
+            None => {
+                // the number of sealed epochs is the number of the current epoch
+                self.epoch == frontier.num_leaves()
+                    && self.path.verify(self.index, value.into(), &current_root)
+            }
+            Some(opening) => {
+                if self.epoch >= frontier.num_leaves()
+                    || opening.position != self.epoch
+                    || opening.forest != frontier.num_leaves()
+                {
+                    return false;
+                }
+
+                match self.path.compute_root(self.index, value.into()) {
+                    Ok(epoch_root) => frontier.verify(epoch_root, opening.clone()),
+                    Err(_) => false,
+                }
+            }
+        }
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{EpochAccumulator, MerkleError, MmrError};
+    use crate::merkle::{int_to_leaf, Rpo256};
+
+    const DEPTH: u8 = 2;
+
+    #[test]
+    fn test_epoch_accumulator_proofs_span_epochs() {
+        let mut accumulator = EpochAccumulator::<DEPTH>::new().unwrap();
+
+        // fill two epochs and start a third one
+        for epoch in 0..2 {
+            for i in 0..4 {
+                accumulator.append(int_to_leaf(epoch * 4 + i)).unwrap();
+            }
+            accumulator.seal_epoch();
+        }
+        accumulator.append(int_to_leaf(8)).unwrap();
+        assert_eq!(accumulator.epoch(), 2);
+
+        let frontier = accumulator.frontier();
+        let current_root = accumulator.current_root();
+        assert_eq!(accumulator.commitment(), Rpo256::merge(&[current_root, frontier.hash_peaks()]));
+
+        for (epoch, index, value) in [(0, 1, 1), (1, 3, 7), (2, 0, 8)] {
+            let proof = accumulator.open(epoch, index).unwrap();
+            assert_eq!(proof.epoch_opening.is_some(), epoch < 2);
+            assert_eq!(accumulator.get(epoch, index).unwrap(), int_to_leaf(value));
+            assert!(proof.verify(int_to_leaf(value), current_root, &frontier));
+            assert!(!proof.verify(int_to_leaf(value + 1), current_root, &frontier));
+        }
+
+        // a proof for a sealed epoch does not verify against another epoch
+        let mut proof = accumulator.open(0, 1).unwrap();
+        proof.epoch = 1;
+        assert!(!proof.verify(int_to_leaf(1), current_root, &frontier));
+
+        // a proof for the current epoch does not verify when relabelled as a sealed epoch
+        let mut proof = accumulator.open(2, 0).unwrap();
+        proof.epoch = 0;
+        assert!(!proof.verify(int_to_leaf(8), current_root, &frontier));
+
+        // a shorter path does not prove an inner node of the current epoch tree as a value
+        let mut proof = accumulator.open(2, 0).unwrap();
+        let inner_node = Rpo256::merge(&[int_to_leaf(8).into(), proof.path[0]]);
+        proof.path = proof.path[1..].to_vec().into();
+        proof.index = 0;
+        assert_eq!(proof.path.compute_root(0, inner_node).unwrap(), current_root);
+        assert!(!proof.verify(inner_node.into(), current_root, &frontier));
+    }
+
+    #[test]
+    fn test_epoch_accumulator_errors() {
+        let mut accumulator = EpochAccumulator::<DEPTH>::new().unwrap();
+        for i in 0..4 {
+            accumulator.append(int_to_leaf(i)).unwrap();
+        }
+        assert_eq!(
+            accumulator.append(int_to_leaf(4)),
+            Err(MmrError::MerkleError(MerkleError::InvalidNumEntries(4)))
+        );
+
+        accumulator.seal_epoch();
+        assert_eq!(accumulator.open(2, 0), Err(MmrError::InvalidPosition(2)));
+        assert_eq!(
+            accumulator.open(1, 0),
+            Err(MmrError::MerkleError(MerkleError::InvalidIndex { depth: DEPTH, value: 0 }))
+        );
+    }
+}
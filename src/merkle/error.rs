@@ -1,35 +1,70 @@
-use alloc::vec::Vec;
+use alloc::{string::ToString, vec::Vec};
 use core::fmt;
 
 use super::{smt::SmtLeafError, MerklePath, NodeIndex, RpoDigest};
+use crate::utils::DeserializationError;
 
+// MERKLE ERROR
+// ================================================================================================
+
+/// Errors returned by the Merkle data structures of this crate.
+///
+/// New variants may be added in the future, so matching on this enum requires a wildcard arm.
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum MerkleError {
+    ConflictingNodeValues {
+        index: NodeIndex,
+        existing: RpoDigest,
+        provided: RpoDigest,
+    },
     ConflictingRoots(Vec<RpoDigest>),
     DepthTooSmall(u8),
     DepthTooBig(u64),
     DuplicateValuesForIndex(u64),
     DuplicateValuesForKey(RpoDigest),
-    InvalidIndex { depth: u8, value: u64 },
-    InvalidDepth { expected: u8, provided: u8 },
-    InvalidSubtreeDepth { subtree_depth: u8, tree_depth: u8 },
+    InvalidIndex {
+        depth: u8,
+        value: u64,
+    },
+    InvalidDepth {
+        expected: u8,
+        provided: u8,
+    },
+    InvalidSubtreeDepth {
+        subtree_depth: u8,
+        tree_depth: u8,
+    },
     InvalidPath(MerklePath),
     InvalidNumEntries(usize),
+    NodeHashNotInStore(RpoDigest),
     NodeNotInSet(NodeIndex),
     NodeNotInStore(RpoDigest, NodeIndex),
     NumLeavesNotPowerOfTwo(usize),
     RootNotInStore(RpoDigest),
     SmtLeaf(SmtLeafError),
+    StaleMutationSet {
+        expected: RpoDigest,
+        actual: RpoDigest,
+    },
 }
 
 impl fmt::Display for MerkleError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         use MerkleError::*;
         match self {
-            ConflictingRoots(roots) => write!(f, "the merkle paths roots do not match {roots:?}"),
+            ConflictingNodeValues { index, existing, provided } => write!(
+                f,
+                "the node with index ({index}) has the value {existing:?}, which conflicts with the provided value {provided:?}"
+            ),
+            ConflictingRoots(roots) => {
+                write!(f, "the merkle paths lead to {} different roots: {roots:?}", roots.len())
+            }
             DepthTooSmall(depth) => write!(f, "the provided depth {depth} is too small"),
             DepthTooBig(depth) => write!(f, "the provided depth {depth} is too big"),
-            DuplicateValuesForIndex(key) => write!(f, "multiple values provided for key {key}"),
+            DuplicateValuesForIndex(index) => {
+                write!(f, "multiple values provided for index {index}")
+            }
             DuplicateValuesForKey(key) => write!(f, "multiple values provided for key {key}"),
             InvalidIndex { depth, value } => {
                 write!(f, "the index value {value} is not valid for the depth {depth}")
@@ -40,8 +75,11 @@ impl fmt::Display for MerkleError {
             InvalidSubtreeDepth { subtree_depth, tree_depth } => {
                 write!(f, "tried inserting a subtree of depth {subtree_depth} into a tree of depth {tree_depth}")
             }
-            InvalidPath(_path) => write!(f, "the provided path is not valid"),
+            InvalidPath(path) => {
+                write!(f, "the provided path of depth {} is not valid", path.depth())
+            }
             InvalidNumEntries(max) => write!(f, "number of entries exceeded the maximum: {max}"),
+            NodeHashNotInStore(hash) => write!(f, "the node {hash:?} is not in the store"),
             NodeNotInSet(index) => write!(f, "the node with index ({index}) is not in the set"),
             NodeNotInStore(hash, index) => {
                 write!(f, "the node {hash:?} with index ({index}) is not in the store")
@@ -49,17 +87,136 @@ impl fmt::Display for MerkleError {
             NumLeavesNotPowerOfTwo(leaves) => {
                 write!(f, "the leaves count {leaves} is not a power of 2")
             }
-            RootNotInStore(root) => write!(f, "the root {:?} is not in the store", root),
+            RootNotInStore(root) => write!(f, "the root {root:?} is not in the store"),
             SmtLeaf(smt_leaf_error) => write!(f, "smt leaf error: {smt_leaf_error}"),
+            StaleMutationSet { expected, actual } => write!(
+                f,
+                "the mutation set was computed against the root {expected:?}, but the tree has the root {actual:?}"
+            ),
         }
     }
 }
 
 #[cfg(feature = "std")]
-impl std::error::Error for MerkleError {}
+impl std::error::Error for MerkleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MerkleError::SmtLeaf(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl MerkleError {
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the index of the node the error refers to, if any.
+    pub fn node_index(&self) -> Option<NodeIndex> {
+        match self {
+            MerkleError::ConflictingNodeValues { index, .. }
+            | MerkleError::NodeNotInSet(index)
+            | MerkleError::NodeNotInStore(_, index) => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Returns the root or the node hash the error refers to, if any.
+    pub fn node_hash(&self) -> Option<RpoDigest> {
+        match self {
+            MerkleError::NodeHashNotInStore(hash)
+            | MerkleError::NodeNotInStore(hash, _)
+            | MerkleError::RootNotInStore(hash) => Some(*hash),
+            _ => None,
+        }
+    }
+
+    /// Returns true if the error was caused by a node missing from the data structure, as opposed
+    /// to invalid arguments.
+    pub fn is_missing_node(&self) -> bool {
+        matches!(
+            self,
+            MerkleError::NodeHashNotInStore(_)
+                | MerkleError::NodeNotInSet(_)
+                | MerkleError::NodeNotInStore(..)
+                | MerkleError::RootNotInStore(_)
+        )
+    }
+}
+
+// CONVERSIONS
+// ================================================================================================
 
 impl From<SmtLeafError> for MerkleError {
     fn from(value: SmtLeafError) -> Self {
         Self::SmtLeaf(value)
     }
 }
+
+impl From<MerkleError> for DeserializationError {
+    fn from(value: MerkleError) -> Self {
+        DeserializationError::InvalidValue(value.to_string())
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::MerkleError;
+    use crate::{
+        merkle::{int_to_node, MerklePath, NodeIndex},
+        utils::DeserializationError,
+    };
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_error_accessors() {
+        let index = NodeIndex::new(3, 5).unwrap();
+        let hash = int_to_node(1);
+
+        let err = MerkleError::NodeNotInStore(hash, index);
+        assert_eq!(err.node_index(), Some(index));
+        assert_eq!(err.node_hash(), Some(hash));
+        assert!(err.is_missing_node());
+
+        let err = MerkleError::NodeNotInSet(index);
+        assert_eq!(err.node_index(), Some(index));
+        assert_eq!(err.node_hash(), None);
+        assert!(err.is_missing_node());
+
+        let err = MerkleError::RootNotInStore(hash);
+        assert_eq!(err.node_index(), None);
+        assert_eq!(err.node_hash(), Some(hash));
+        assert!(err.is_missing_node());
+
+        let err = MerkleError::InvalidPath(MerklePath::new(vec![hash]));
+        assert_eq!(err.node_index(), None);
+        assert_eq!(err.node_hash(), None);
+        assert!(!err.is_missing_node());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_error_source() {
+        use super::SmtLeafError;
+        use std::error::Error;
+
+        let leaf_err = SmtLeafError::InvalidNumEntriesForMultiple(1);
+        let err = MerkleError::from(leaf_err.clone());
+        assert_eq!(err, MerkleError::SmtLeaf(leaf_err.clone()));
+        assert_eq!(err.source().unwrap().to_string(), leaf_err.to_string());
+
+        assert!(MerkleError::NodeNotInSet(NodeIndex::root()).source().is_none());
+    }
+
+    #[test]
+    fn test_error_into_deserialization_error() {
+        let err = MerkleError::DepthTooBig(65);
+        assert!(matches!(
+            DeserializationError::from(err.clone()),
+            DeserializationError::InvalidValue(message) if message == err.to_string()
+        ));
+    }
+}
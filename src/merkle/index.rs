@@ -1,6 +1,6 @@
-use core::fmt::Display;
+use core::{fmt::Display, iter, ops::RangeInclusive};
 
-use super::{Felt, MerkleError, RpoDigest};
+use super::{Felt, MerkleError, RpoDigest, MAX_TREE_DEPTH};
 use crate::utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
 // NODE INDEX
@@ -97,9 +97,56 @@ impl NodeIndex {
         self
     }
 
+    /// Returns parent index of the current node, or the root index if the current node is the
+    /// root.
+    pub const fn parent(mut self) -> Self {
+        self.depth = self.depth.saturating_sub(1);
+        self.value >>= 1;
+        self
+    }
+
     // PROVIDERS
     // --------------------------------------------------------------------------------------------
 
+    /// Returns an iterator over the ancestors of the current node, starting from its parent and
+    /// ending with the root.
+    pub fn ancestors(self) -> impl Iterator<Item = NodeIndex> {
+        iter::successors(Some(self), |index| (!index.is_root()).then(|| index.parent())).skip(1)
+    }
+
+    /// Returns true if the current node is a strict ancestor of `other`, i.e. if `other` is in the
+    /// subtree rooted at the current node and is not the current node itself.
+    pub const fn is_ancestor_of(&self, other: &NodeIndex) -> bool {
+        if self.depth >= other.depth {
+            return false;
+        }
+
+        match other.value.checked_shr((other.depth - self.depth) as u32) {
+            Some(value) => value == self.value,
+            // the current node is the root and `other` is at depth 64
+            None => true,
+        }
+    }
+
+    /// Returns the range of the values of the nodes at the specified `depth` which are in the
+    /// subtree rooted at the current node.
+    ///
+    /// # Errors
+    /// Returns an error if `depth` is smaller than the depth of the current node, or is greater
+    /// than [MAX_TREE_DEPTH].
+    pub fn subtree_range(&self, depth: u8) -> Result<RangeInclusive<u64>, MerkleError> {
+        if depth < self.depth {
+            return Err(MerkleError::DepthTooSmall(depth));
+        } else if depth > MAX_TREE_DEPTH {
+            return Err(MerkleError::DepthTooBig(depth as u64));
+        }
+
+        let delta = (depth - self.depth) as u32;
+        let start = self.value.checked_shl(delta).unwrap_or(0);
+        let len_mask = u64::MAX.checked_shr(u64::BITS - delta).unwrap_or(0);
+        Ok(start..=start | len_mask)
+    }
+
     /// Builds a node to be used as input of a hash function when computing a Merkle path.
     ///
     /// Will evaluate the parity of the current instance to define the result.
@@ -182,6 +229,7 @@ impl Deserializable for NodeIndex {
 
 #[cfg(test)]
 mod tests {
+    use alloc::vec::Vec;
     use proptest::prelude::*;
 
     use super::*;
@@ -210,6 +258,44 @@ mod tests {
         assert!(NodeIndex::new(64, u64::MAX).is_ok());
     }
 
+    #[test]
+    fn test_node_index_navigation() {
+        let index = NodeIndex::new(3, 5).unwrap();
+
+        assert_eq!(index.parent(), NodeIndex::new(2, 2).unwrap());
+        assert_eq!(index.sibling(), NodeIndex::new(3, 4).unwrap());
+        assert_eq!(index.left_child(), NodeIndex::new(4, 10).unwrap());
+        assert_eq!(index.right_child(), NodeIndex::new(4, 11).unwrap());
+        assert_eq!(NodeIndex::root().parent(), NodeIndex::root());
+
+        let ancestors: Vec<NodeIndex> = index.ancestors().collect();
+        assert_eq!(
+            ancestors,
+            [NodeIndex::new(2, 2).unwrap(), NodeIndex::new(1, 1).unwrap(), NodeIndex::root()]
+        );
+        assert_eq!(NodeIndex::root().ancestors().count(), 0);
+
+        for ancestor in ancestors {
+            assert!(ancestor.is_ancestor_of(&index));
+            assert!(!index.is_ancestor_of(&ancestor));
+        }
+        assert!(!index.is_ancestor_of(&index));
+        assert!(!index.sibling().is_ancestor_of(&index.left_child()));
+        assert!(NodeIndex::root().is_ancestor_of(&NodeIndex::new(64, u64::MAX).unwrap()));
+    }
+
+    #[test]
+    fn test_node_index_subtree_range() {
+        let index = NodeIndex::new(2, 1).unwrap();
+        assert_eq!(index.subtree_range(2), Ok(1..=1));
+        assert_eq!(index.subtree_range(4), Ok(4..=7));
+        assert_eq!(NodeIndex::root().subtree_range(64), Ok(0..=u64::MAX));
+        assert_eq!(NodeIndex::new(1, 1).unwrap().subtree_range(64), Ok(1 << 63..=u64::MAX));
+
+        assert_eq!(index.subtree_range(1), Err(MerkleError::DepthTooSmall(1)));
+        assert_eq!(index.subtree_range(65), Err(MerkleError::DepthTooBig(65)));
+    }
+
     prop_compose! {
         fn node_index()(value in 0..2u64.pow(u64::BITS - 1)) -> NodeIndex {
             // unwrap never panics because the range of depth is 0..u64::BITS
@@ -1,11 +1,44 @@
-use alloc::{string::String, vec::Vec};
-use core::{fmt, ops::Deref, slice};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    string::String,
+    vec::Vec,
+};
+use core::{
+    fmt,
+    ops::{Deref, Range},
+    slice,
+};
 
 use winter_math::log2;
 
-use super::{InnerNodeInfo, MerkleError, MerklePath, NodeIndex, Rpo256, RpoDigest, Word};
+use super::{
+    InnerNodeInfo, MerkleError, MerklePath, MultiProof, NodeIndex, Rpo256, RpoDigest, Word,
+    EMPTY_WORD,
+};
 use crate::utils::{uninit_vector, word_to_hex};
 
+// CONSTANTS
+// ================================================================================================
+
+/// Number of nodes of a level of a [MerkleTree] computed by a single task when the tree is built
+/// using multiple threads. Chunks of this size keep the nodes written by different threads apart.
+#[cfg(feature = "rayon")]
+const PARALLEL_CHUNK_SIZE: usize = 1 << 10;
+
+// PADDING POLICY
+// ================================================================================================
+
+/// Specifies how [MerkleTree::with_padding] fills the leaves of a tree beyond the provided ones,
+/// up to the next power of two.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PaddingPolicy {
+    /// The tree is padded with [EMPTY_WORD] leaves.
+    EmptyWord,
+
+    /// The tree is padded with copies of the last provided leaf.
+    DuplicateLast,
+}
+
 // MERKLE TREE
 // ================================================================================================
 
@@ -44,20 +77,68 @@ impl MerkleTree {
             *node = RpoDigest::from(*leaf);
         });
 
-        // re-interpret nodes as an array of two nodes fused together
-        // Safety: `nodes` will never move here as it is not bound to an external lifetime (i.e.
-        // `self`).
-        let ptr = nodes.as_ptr() as *const [RpoDigest; 2];
-        let pairs = unsafe { slice::from_raw_parts(ptr, n) };
-
         // calculate all internal tree nodes
-        for i in (1..n).rev() {
-            nodes[i] = Rpo256::merge(&pairs[i]);
+        build_inner_nodes(&mut nodes);
+
+        Ok(Self { nodes })
+    }
+
+    /// Returns a Merkle tree instantiated from the provided leaves, given as digests.
+    ///
+    /// The resulting tree is the same as the one built by [MerkleTree::new] from the [Word]s of
+    /// the digests; this merely avoids converting the leaves back and forth.
+    ///
+    /// # Errors
+    /// Returns an error if the number of leaves is smaller than two or is not a power of two.
+    pub fn from_digests(leaves: Vec<RpoDigest>) -> Result<Self, MerkleError> {
+        let n = leaves.len();
+        if n <= 1 {
+            return Err(MerkleError::DepthTooSmall(n as u8));
+        } else if !n.is_power_of_two() {
+            return Err(MerkleError::NumLeavesNotPowerOfTwo(n));
         }
 
+        // the inner nodes occupy the first half of the nodes vector, followed by the leaves
+        let mut nodes = Vec::with_capacity(2 * n);
+        nodes.resize(n, RpoDigest::default());
+        nodes.extend(leaves);
+
+        build_inner_nodes(&mut nodes);
+
         Ok(Self { nodes })
     }
 
+    /// Returns a Merkle tree instantiated from the provided leaves, padded up to the next power of
+    /// two as specified by `padding`.
+    ///
+    /// A single leaf is padded to a tree with two leaves. Note that the padding is not recorded in
+    /// the tree, so the same tree is returned for the provided leaves and for the leaves with the
+    /// padding appended.
+    ///
+    /// # Errors
+    /// Returns an error if no leaves are provided.
+    pub fn with_padding<T>(leaves: T, padding: PaddingPolicy) -> Result<Self, MerkleError>
+    where
+        T: AsRef<[Word]>,
+    {
+        let leaves = leaves.as_ref();
+        let Some(&last) = leaves.last() else {
+            return Err(MerkleError::DepthTooSmall(0));
+        };
+
+        let num_leaves = leaves.len().next_power_of_two().max(2);
+        let pad = match padding {
+            PaddingPolicy::EmptyWord => EMPTY_WORD,
+            PaddingPolicy::DuplicateLast => last,
+        };
+
+        let mut padded = Vec::with_capacity(num_leaves);
+        padded.extend_from_slice(leaves);
+        padded.resize(num_leaves, pad);
+
+        Self::new(padded)
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -119,6 +200,126 @@ impl MerkleTree {
         Ok(path.into())
     }
 
+    /// Returns a [MultiProof] opening the leaves at the specified index values.
+    ///
+    /// Sibling nodes shared by several openings are included in the proof only once, and nodes
+    /// which can be computed from the opened leaves are omitted altogether. The proof can be
+    /// verified with [MerkleTree::verify_batch].
+    ///
+    /// # Errors
+    /// Returns an error if any of the specified index values is not a valid leaf value for this
+    /// tree.
+    pub fn prove_batch(&self, indices: &[u64]) -> Result<MultiProof, MerkleError> {
+        let depth = self.depth();
+        let mut siblings = BTreeMap::new();
+        let mut computable = BTreeSet::new();
+
+        for &index_value in indices {
+            let mut index = NodeIndex::new(depth, index_value)?;
+
+            // every node from the opened leaf up to the root can be computed by the verifier
+            computable.insert(index);
+            for _ in 0..depth {
+                let sibling = index.sibling();
+                siblings.insert(sibling, self.nodes[sibling.to_scalar_index() as usize]);
+                index.move_up();
+                computable.insert(index);
+            }
+        }
+
+        Ok(MultiProof::new(
+            siblings.into_iter().filter(|(index, _)| !computable.contains(index)),
+        ))
+    }
+
+    /// Returns a [MultiProof] opening the contiguous leaves in the specified range.
+    ///
+    /// Only the siblings along the paths of the first and the last leaf of the range are needed,
+    /// since every other node is computed from the opened leaves. The proof can be verified with
+    /// [MerkleTree::verify_range].
+    ///
+    /// # Errors
+    /// Returns an error if the range is empty, or if it extends beyond the leaves of this tree.
+    pub fn prove_range(&self, range: Range<u64>) -> Result<MultiProof, MerkleError> {
+        let depth = self.depth();
+        if range.is_empty() {
+            return Err(MerkleError::InvalidIndex { depth, value: range.start });
+        }
+        let mut first = NodeIndex::new(depth, range.start)?;
+        let mut last = NodeIndex::new(depth, range.end - 1)?;
+
+        // at each level, the nodes between the first and the last node are computable; only the
+        // left sibling of the first one and the right sibling of the last one may be needed
+        let mut nodes = Vec::with_capacity(2 * depth as usize);
+        for _ in 0..depth {
+            if first.is_value_odd() {
+                let sibling = first.sibling();
+                nodes.push((sibling, self.nodes[sibling.to_scalar_index() as usize]));
+            }
+            if !last.is_value_odd() {
+                let sibling = last.sibling();
+                nodes.push((sibling, self.nodes[sibling.to_scalar_index() as usize]));
+            }
+            first.move_up();
+            last.move_up();
+        }
+
+        Ok(MultiProof::new(nodes))
+    }
+
+    // PROOF VERIFIER
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns `true` if `leaves` are the leaves at the specified index values of a Merkle tree of
+    /// the specified `depth` and `root`, as attested by a `proof` produced by
+    /// [MerkleTree::prove_batch].
+    ///
+    /// Returns `false` if the number of leaves does not match the number of indices, or if any of
+    /// the index values is not valid for the specified depth.
+    pub fn verify_batch(
+        root: &RpoDigest,
+        depth: u8,
+        indices: &[u64],
+        leaves: &[Word],
+        proof: &MultiProof,
+    ) -> bool {
+        if indices.len() != leaves.len() {
+            return false;
+        }
+
+        let openings = indices
+            .iter()
+            .zip(leaves)
+            .map(|(&index_value, leaf)| {
+                NodeIndex::new(depth, index_value).map(|index| (index, RpoDigest::from(*leaf)))
+            })
+            .collect::<Result<Vec<_>, _>>();
+
+        match openings {
+            Ok(openings) => proof.verify(openings, root),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns `true` if `leaves` are the contiguous leaves starting at index value `start` of a
+    /// Merkle tree of the specified `depth` and `root`, as attested by a `proof` produced by
+    /// [MerkleTree::prove_range].
+    pub fn verify_range(
+        root: &RpoDigest,
+        depth: u8,
+        start: u64,
+        leaves: &[Word],
+        proof: &MultiProof,
+    ) -> bool {
+        let indices = (0..leaves.len() as u64)
+            .map(|i| start.checked_add(i))
+            .collect::<Option<Vec<_>>>();
+        match indices {
+            Some(indices) => Self::verify_batch(root, depth, &indices, leaves, proof),
+            None => false,
+        }
+    }
+
     // ITERATORS
     // --------------------------------------------------------------------------------------------
 
@@ -142,6 +343,29 @@ impl MerkleTree {
         }
     }
 
+    /// Returns the nodes of this [MerkleTree] at the specified depth, ordered by their index value.
+    ///
+    /// Depth 0 holds only the root, while the depth of the tree holds its leaves.
+    ///
+    /// # Errors
+    /// Returns an error if the specified depth is greater than the depth of the tree.
+    pub fn nodes_at_depth(&self, depth: u8) -> Result<&[RpoDigest], MerkleError> {
+        if depth > self.depth() {
+            return Err(MerkleError::DepthTooBig(depth as u64));
+        }
+
+        let start = 1 << depth;
+        Ok(&self.nodes[start..2 * start])
+    }
+
+    /// Returns an iterator over every inner node of this [MerkleTree], together with its index.
+    ///
+    /// The nodes are visited depth-first, in pre-order: each node is followed by the inner nodes
+    /// of its left subtree, and then by the inner nodes of its right subtree.
+    pub fn inner_nodes_with_index(&self) -> InnerNodeWithIndexIterator {
+        InnerNodeWithIndexIterator { nodes: &self.nodes, pos: 1 }
+    }
+
     // STATE MUTATORS
     // --------------------------------------------------------------------------------------------
 
@@ -180,6 +404,206 @@ impl MerkleTree {
 
         Ok(())
     }
+
+    /// Replaces the leaves at the specified indices with the provided values, returning the new
+    /// root of the tree.
+    ///
+    /// Each inner node affected by the updates is recomputed exactly once, which is cheaper than
+    /// updating the leaves one by one when their paths share ancestors. If an index is updated
+    /// more than once, the last value is kept.
+    ///
+    /// # Errors
+    /// Returns an error if any of the specified index values is not a valid leaf value for this
+    /// tree, in which case the tree is left unchanged.
+    pub fn update_leaves(&mut self, updates: &[(u64, Word)]) -> Result<RpoDigest, MerkleError> {
+        let depth = self.depth();
+        let mut positions = updates
+            .iter()
+            .map(|&(index_value, _)| {
+                NodeIndex::new(depth, index_value).map(|index| index.to_scalar_index() as usize)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // update the leaves
+        for (&pos, (_, value)) in positions.iter().zip(updates) {
+            self.nodes[pos] = (*value).into();
+        }
+
+        // traverse to the root level by level, updating each affected node once; halving the
+        // positions of a level keeps them sorted, so duplicates are adjacent
+        positions.sort_unstable();
+        positions.dedup();
+        for _ in 0..depth {
+            for pos in positions.iter_mut() {
+                *pos /= 2;
+            }
+            positions.dedup();
+
+            for &pos in positions.iter() {
+                self.nodes[pos] = Rpo256::merge(&[self.nodes[2 * pos], self.nodes[2 * pos + 1]]);
+            }
+        }
+
+        Ok(self.root())
+    }
+}
+
+// MERKLE TREE BUILDER
+// ================================================================================================
+
+/// Computes the root of a [MerkleTree] from a stream of leaves.
+///
+/// Leaves are hashed as soon as they are pushed, and only the roots of the complete subtrees built
+/// so far are kept, so the builder requires memory logarithmic in the number of leaves. The
+/// resulting root matches the root of the [MerkleTree] built from the same leaves.
+#[derive(Debug, Clone, Default)]
+pub struct MerkleTreeBuilder {
+    /// Roots of the complete subtrees built so far, with their heights, in strictly decreasing
+    /// order of height.
+    subtrees: Vec<(u8, RpoDigest)>,
+    num_leaves: u64,
+    last_leaf: Option<Word>,
+}
+
+impl MerkleTreeBuilder {
+    /// Returns a new [MerkleTreeBuilder] without any leaves.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of leaves pushed into this builder.
+    pub fn num_leaves(&self) -> u64 {
+        self.num_leaves
+    }
+
+    /// Appends a leaf to the tree.
+    pub fn push(&mut self, leaf: Word) {
+        self.push_subtree(0, leaf.into());
+        self.num_leaves += 1;
+        self.last_leaf = Some(leaf);
+    }
+
+    /// Returns the root of the tree built from the pushed leaves.
+    ///
+    /// # Errors
+    /// Returns an error if the number of leaves is smaller than two or is not a power of two.
+    pub fn finalize(self) -> Result<RpoDigest, MerkleError> {
+        let n = self.num_leaves;
+        if n <= 1 {
+            return Err(MerkleError::DepthTooSmall(n as u8));
+        } else if !n.is_power_of_two() {
+            return Err(MerkleError::NumLeavesNotPowerOfTwo(n as usize));
+        }
+
+        debug_assert_eq!(self.subtrees.len(), 1);
+        Ok(self.subtrees[0].1)
+    }
+
+    /// Returns the root of the tree built from the pushed leaves, padded up to the next power of
+    /// two as specified by `padding`.
+    ///
+    /// The root matches the root of the tree returned by [MerkleTree::with_padding] for the same
+    /// leaves.
+    ///
+    /// # Errors
+    /// Returns an error if no leaves were pushed.
+    pub fn finalize_with_padding(
+        mut self,
+        padding: PaddingPolicy,
+    ) -> Result<RpoDigest, MerkleError> {
+        let Some(last) = self.last_leaf else {
+            return Err(MerkleError::DepthTooSmall(0));
+        };
+
+        // the root of a subtree made of padding leaves, grown as taller subtrees are needed
+        let (mut pad_height, mut pad) = match padding {
+            PaddingPolicy::EmptyWord => (0, RpoDigest::from(EMPTY_WORD)),
+            PaddingPolicy::DuplicateLast => (0, RpoDigest::from(last)),
+        };
+
+        // complete the smallest subtree with padding until a single tree with at least two
+        // leaves remains
+        while self.subtrees.len() > 1 || self.subtrees[0].0 == 0 {
+            let height = self.subtrees[self.subtrees.len() - 1].0;
+            while pad_height < height {
+                pad = Rpo256::merge(&[pad, pad]);
+                pad_height += 1;
+            }
+            self.push_subtree(height, pad);
+        }
+
+        Ok(self.subtrees[0].1)
+    }
+
+    /// Pushes the root of a complete subtree of the specified height, merging it with the
+    /// previous subtrees of the same height.
+    fn push_subtree(&mut self, mut height: u8, mut node: RpoDigest) {
+        while let Some(&(left_height, left)) = self.subtrees.last() {
+            if left_height != height {
+                break;
+            }
+            self.subtrees.pop();
+            node = Rpo256::merge(&[left, node]);
+            height += 1;
+        }
+        self.subtrees.push((height, node));
+    }
+}
+
+impl Extend<Word> for MerkleTreeBuilder {
+    fn extend<T: IntoIterator<Item = Word>>(&mut self, iter: T) {
+        for leaf in iter {
+            self.push(leaf);
+        }
+    }
+}
+
+// TREE CONSTRUCTION
+// ================================================================================================
+
+/// Computes the inner nodes of a Merkle tree whose `nodes` are laid out as in [MerkleTree], with
+/// the leaves already set.
+#[cfg(not(feature = "rayon"))]
+fn build_inner_nodes(nodes: &mut [RpoDigest]) {
+    let n = nodes.len() / 2;
+
+    // re-interpret nodes as an array of two nodes fused together
+    // Safety: `nodes` is a slice, so it cannot be moved or resized while `pairs` is in use.
+    let ptr = nodes.as_ptr() as *const [RpoDigest; 2];
+    let pairs = unsafe { slice::from_raw_parts(ptr, n) };
+
+    for i in (1..n).rev() {
+        nodes[i] = Rpo256::merge(&pairs[i]);
+    }
+}
+
+/// Computes the inner nodes of a Merkle tree whose `nodes` are laid out as in [MerkleTree], with
+/// the leaves already set.
+///
+/// The tree is built level by level, starting from the parents of the leaves; the nodes of each
+/// level are computed in parallel, in chunks of [PARALLEL_CHUNK_SIZE] nodes.
+#[cfg(feature = "rayon")]
+fn build_inner_nodes(nodes: &mut [RpoDigest]) {
+    use rayon::prelude::*;
+
+    let mut level_size = nodes.len() / 4;
+    while level_size > 0 {
+        // the nodes of a level are followed by their children
+        let (upper, lower) = nodes.split_at_mut(2 * level_size);
+        let parents = &mut upper[level_size..];
+        let children = &lower[..2 * level_size];
+
+        parents
+            .par_chunks_mut(PARALLEL_CHUNK_SIZE)
+            .zip(children.par_chunks(2 * PARALLEL_CHUNK_SIZE))
+            .for_each(|(parents, children)| {
+                for (parent, pair) in parents.iter_mut().zip(children.chunks_exact(2)) {
+                    *parent = Rpo256::merge(&[pair[0], pair[1]]);
+                }
+            });
+
+        level_size /= 2;
+    }
 }
 
 // CONVERSIONS
@@ -197,8 +621,7 @@ impl TryFrom<&[RpoDigest]> for MerkleTree {
     type Error = MerkleError;
 
     fn try_from(value: &[RpoDigest]) -> Result<Self, Self::Error> {
-        let value: Vec<Word> = value.iter().map(|v| *v.deref()).collect();
-        MerkleTree::new(value)
+        MerkleTree::from_digests(value.to_vec())
     }
 }
 
@@ -235,6 +658,47 @@ impl<'a> Iterator for InnerNodeIterator<'a> {
     }
 }
 
+/// A depth-first iterator over every inner node of the [MerkleTree], together with its index.
+pub struct InnerNodeWithIndexIterator<'a> {
+    nodes: &'a [RpoDigest],
+    /// The scalar index of the next node to visit, or 0 once every node was visited.
+    pos: usize,
+}
+
+impl<'a> Iterator for InnerNodeWithIndexIterator<'a> {
+    type Item = (NodeIndex, InnerNodeInfo);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos == 0 {
+            return None;
+        }
+
+        let pos = self.pos;
+        let depth = pos.ilog2();
+        let index = NodeIndex::make(depth as u8, (pos - (1 << depth)) as u64);
+        let node = InnerNodeInfo {
+            value: self.nodes[pos],
+            left: self.nodes[2 * pos],
+            right: self.nodes[2 * pos + 1],
+        };
+
+        // descend into the left child if it is an inner node; otherwise, move up past the right
+        // children, and on to the right sibling of the first left child found. the root is the
+        // only node reached with an odd index and no parent, which ends the traversal.
+        if 2 * pos < self.nodes.len() / 2 {
+            self.pos = 2 * pos;
+        } else {
+            let mut next = pos;
+            while next & 1 == 1 {
+                next >>= 1;
+            }
+            self.pos = if next == 0 { 0 } else { next + 1 };
+        }
+
+        Some((index, node))
+    }
+}
+
 // UTILITY FUNCTIONS
 // ================================================================================================
 
@@ -329,6 +793,66 @@ mod tests {
         assert_eq!(root, tree.root());
     }
 
+    #[test]
+    fn build_large_merkle_tree() {
+        // enough leaves for the levels to be split into chunks when built using multiple threads
+        let leaves: Vec<RpoDigest> = (0..1 << 12).map(int_to_node).collect();
+        let tree = super::MerkleTree::new(digests_to_words(&leaves)).unwrap();
+
+        let mut level = leaves;
+        while level.len() > 1 {
+            level = level.chunks(2).map(|pair| Rpo256::merge(&[pair[0], pair[1]])).collect();
+        }
+        assert_eq!(tree.root(), level[0]);
+    }
+
+    #[test]
+    fn build_merkle_tree_from_digests() {
+        let tree = MerkleTree::from_digests(LEAVES8.to_vec()).unwrap();
+        let expected = MerkleTree::new(digests_to_words(&LEAVES8)).unwrap();
+        assert_eq!(tree, expected);
+        assert_eq!(MerkleTree::try_from(&LEAVES8[..]).unwrap(), expected);
+
+        // nodes and paths are retrieved as for any other tree
+        let index = NodeIndex::make(3, 5);
+        assert_eq!(tree.get_node(index).unwrap(), LEAVES8[5]);
+        assert_eq!(tree.get_path(index).unwrap(), expected.get_path(index).unwrap());
+
+        assert_eq!(
+            MerkleTree::from_digests(LEAVES8[..1].to_vec()),
+            Err(MerkleError::DepthTooSmall(1))
+        );
+        assert_eq!(
+            MerkleTree::from_digests(LEAVES8[..6].to_vec()),
+            Err(MerkleError::NumLeavesNotPowerOfTwo(6))
+        );
+    }
+
+    #[test]
+    fn build_merkle_tree_with_padding() {
+        let leaves = digests_to_words(&LEAVES8[..5]);
+
+        let tree = super::MerkleTree::with_padding(&leaves, PaddingPolicy::EmptyWord).unwrap();
+        let mut padded = leaves.clone();
+        padded.resize(8, EMPTY_WORD);
+        assert_eq!(tree, super::MerkleTree::new(padded).unwrap());
+
+        let tree = super::MerkleTree::with_padding(&leaves, PaddingPolicy::DuplicateLast).unwrap();
+        let mut padded = leaves.clone();
+        padded.resize(8, leaves[4]);
+        assert_eq!(tree, super::MerkleTree::new(padded).unwrap());
+
+        // power-of-two leaf counts are not padded, and a single leaf is padded to two
+        let tree = super::MerkleTree::with_padding(&leaves[..4], PaddingPolicy::EmptyWord).unwrap();
+        assert_eq!(tree, super::MerkleTree::new(&leaves[..4]).unwrap());
+        let tree = super::MerkleTree::with_padding(&leaves[..1], PaddingPolicy::EmptyWord).unwrap();
+        assert_eq!(tree.depth(), 1);
+
+        assert!(
+            super::MerkleTree::with_padding(Vec::<Word>::new(), PaddingPolicy::EmptyWord).is_err()
+        );
+    }
+
     #[test]
     fn get_leaf() {
         let tree = super::MerkleTree::new(digests_to_words(&LEAVES4)).unwrap();
@@ -387,6 +911,30 @@ mod tests {
         assert_eq!(expected_tree.nodes, tree.nodes);
     }
 
+    #[test]
+    fn update_leaves() {
+        let mut tree = super::MerkleTree::new(digests_to_words(&LEAVES8)).unwrap();
+
+        let updates = [
+            (6, int_to_leaf(60)),
+            (1, int_to_leaf(10)),
+            (6, int_to_leaf(61)),
+            (7, int_to_leaf(70)),
+        ];
+        let mut expected = tree.clone();
+        for (index, value) in updates {
+            expected.update_leaf(index, value).unwrap();
+        }
+
+        assert_eq!(tree.update_leaves(&updates), Ok(expected.root()));
+        assert_eq!(tree, expected);
+
+        // an invalid index leaves the tree unchanged
+        let updates = [(0, int_to_leaf(1)), (8, int_to_leaf(80))];
+        assert!(tree.update_leaves(&updates).is_err());
+        assert_eq!(tree, expected);
+    }
+
     #[test]
     fn nodes() -> Result<(), MerkleError> {
         let tree = super::MerkleTree::new(digests_to_words(&LEAVES4)).unwrap();
@@ -409,6 +957,129 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn prove_batch() {
+        let tree = super::MerkleTree::new(digests_to_words(&LEAVES8)).unwrap();
+        let leaves = digests_to_words(&LEAVES8);
+        let root = tree.root();
+
+        // adjacent leaves share all their siblings but one
+        let proof = tree.prove_batch(&[2, 3]).unwrap();
+        assert_eq!(proof.num_nodes(), 2);
+        assert!(MerkleTree::verify_batch(&root, 3, &[2, 3], &leaves[2..4], &proof));
+
+        // a proof of scattered leaves verifies as well, in any order
+        let proof = tree.prove_batch(&[6, 0, 5]).unwrap();
+        let opened = [leaves[6], leaves[0], leaves[5]];
+        assert!(MerkleTree::verify_batch(&root, 3, &[6, 0, 5], &opened, &proof));
+
+        // wrong leaves, indices or depth do not verify
+        let wrong = [leaves[6], leaves[1], leaves[5]];
+        assert!(!MerkleTree::verify_batch(&root, 3, &[6, 0, 5], &wrong, &proof));
+        assert!(!MerkleTree::verify_batch(&root, 3, &[6, 1, 5], &opened, &proof));
+        assert!(!MerkleTree::verify_batch(&root, 2, &[6, 0, 5], &opened, &proof));
+        assert!(!MerkleTree::verify_batch(&root, 3, &[6, 0], &opened, &proof));
+
+        // indices must be valid leaves of the tree
+        assert!(tree.prove_batch(&[8]).is_err());
+    }
+
+    #[test]
+    fn prove_range() {
+        let tree = super::MerkleTree::new(digests_to_words(&LEAVES8)).unwrap();
+        let leaves = digests_to_words(&LEAVES8);
+        let root = tree.root();
+
+        // the proof only holds the siblings along the boundary paths
+        let proof = tree.prove_range(1..6).unwrap();
+        assert_eq!(proof.num_nodes(), 2);
+        assert!(MerkleTree::verify_range(&root, 3, 1, &leaves[1..6], &proof));
+        assert_eq!(proof, tree.prove_batch(&[1, 2, 3, 4, 5]).unwrap());
+
+        // a range covering the whole tree needs no sibling at all
+        let proof = tree.prove_range(0..8).unwrap();
+        assert_eq!(proof.num_nodes(), 0);
+        assert!(MerkleTree::verify_range(&root, 3, 0, &leaves, &proof));
+
+        // shifted, shortened or modified ranges do not verify
+        let proof = tree.prove_range(2..4).unwrap();
+        assert!(MerkleTree::verify_range(&root, 3, 2, &leaves[2..4], &proof));
+        assert!(!MerkleTree::verify_range(&root, 3, 3, &leaves[2..4], &proof));
+        assert!(!MerkleTree::verify_range(&root, 3, 2, &leaves[2..3], &proof));
+        assert!(!MerkleTree::verify_range(&root, 3, 2, &leaves[3..5], &proof));
+
+        // the range must be non-empty and within the leaves of the tree
+        assert!(tree.prove_range(3..3).is_err());
+        assert!(tree.prove_range(6..9).is_err());
+    }
+
+    #[test]
+    fn merkle_tree_builder() {
+        let leaves = digests_to_words(&LEAVES8);
+
+        let mut builder = MerkleTreeBuilder::new();
+        builder.extend(leaves.iter().copied());
+        assert_eq!(builder.num_leaves(), 8);
+        assert_eq!(builder.finalize().unwrap(), MerkleTree::new(&leaves).unwrap().root());
+
+        // any number of leaves can be padded to a power of two
+        for n in 1..=leaves.len() {
+            for padding in [PaddingPolicy::EmptyWord, PaddingPolicy::DuplicateLast] {
+                let mut builder = MerkleTreeBuilder::new();
+                builder.extend(leaves[..n].iter().copied());
+                let expected = MerkleTree::with_padding(&leaves[..n], padding).unwrap().root();
+                assert_eq!(builder.finalize_with_padding(padding).unwrap(), expected);
+            }
+        }
+
+        // without padding, the number of leaves must be a power of two greater than one
+        let mut builder = MerkleTreeBuilder::new();
+        builder.extend(leaves[..3].iter().copied());
+        assert_eq!(builder.finalize(), Err(MerkleError::NumLeavesNotPowerOfTwo(3)));
+        assert_eq!(MerkleTreeBuilder::new().finalize(), Err(MerkleError::DepthTooSmall(0)));
+        assert_eq!(
+            MerkleTreeBuilder::new().finalize_with_padding(PaddingPolicy::EmptyWord),
+            Err(MerkleError::DepthTooSmall(0))
+        );
+    }
+
+    #[test]
+    fn nodes_at_depth() {
+        let tree = super::MerkleTree::new(digests_to_words(&LEAVES8)).unwrap();
+        assert_eq!(tree.nodes_at_depth(0).unwrap(), &[tree.root()]);
+        assert_eq!(tree.nodes_at_depth(3).unwrap(), &LEAVES8);
+        for depth in 1..=3 {
+            let nodes = tree.nodes_at_depth(depth).unwrap();
+            assert_eq!(nodes.len(), 1 << depth);
+            for (i, node) in nodes.iter().enumerate() {
+                assert_eq!(*node, tree.get_node(NodeIndex::make(depth, i as u64)).unwrap());
+            }
+        }
+        assert_eq!(tree.nodes_at_depth(4), Err(MerkleError::DepthTooBig(4)));
+    }
+
+    #[test]
+    fn inner_nodes_with_index() {
+        let tree = super::MerkleTree::new(digests_to_words(&LEAVES8)).unwrap();
+        let nodes: Vec<(NodeIndex, InnerNodeInfo)> = tree.inner_nodes_with_index().collect();
+
+        // the inner nodes are visited depth-first
+        let indices: Vec<(u8, u64)> =
+            nodes.iter().map(|(index, _)| (index.depth(), index.value())).collect();
+        assert_eq!(indices, vec![(0, 0), (1, 0), (2, 0), (2, 1), (1, 1), (2, 2), (2, 3)]);
+
+        // each node matches its index and children
+        for (index, node) in nodes {
+            let value = tree.nodes_at_depth(index.depth()).unwrap()[index.value() as usize];
+            assert_eq!(node.value, value);
+            assert_eq!(node.left, tree.get_node(index.left_child()).unwrap());
+            assert_eq!(node.right, tree.get_node(index.right_child()).unwrap());
+        }
+
+        // the iterator yields the same nodes as the unordered one
+        assert_eq!(tree.inner_nodes_with_index().count(), tree.inner_nodes().count());
+    }
+
     proptest! {
         #[test]
         fn arbitrary_word_can_be_represented_as_digest(
@@ -0,0 +1,69 @@
+use super::{
+    super::MerklePath, bit::TrueBitPositionIterator, full::high_bitmask,
+    leaf_to_corresponding_tree, MmrPeaks,
+};
+use alloc::vec::Vec;
+
+// MMR CONSISTENCY PROOF
+// ================================================================================================
+
+/// A proof that a version of an MMR is an append-only extension of an earlier version of the same
+/// MMR.
+///
+/// Every tree of the earlier version is a subtree of a tree of the later version. The proof
+/// consists of an authentication path from each peak of the earlier version up to the peak of the
+/// later version containing it, which allows detecting rewrites of the history of the MMR given
+/// only the peaks of both versions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct MmrConsistencyProof {
+    /// The number of leaves of the earlier version of the MMR.
+    pub old_forest: usize,
+
+    /// The number of leaves of the later version of the MMR.
+    pub new_forest: usize,
+
+    /// The authentication paths of the peaks of the earlier version, starting from the peak with
+    /// most children, to the one with least. Each path goes from the sibling of the old peak up to
+    /// and excluding the new peak.
+    pub paths: Vec<MerklePath>,
+}
+
+impl MmrConsistencyProof {
+    /// Returns true if this proof shows that the MMR with `new_peaks` is an append-only extension
+    /// of the MMR with `old_peaks`.
+    pub fn verify(&self, old_peaks: &MmrPeaks, new_peaks: &MmrPeaks) -> bool {
+        if old_peaks.num_leaves() != self.old_forest
+            || new_peaks.num_leaves() != self.new_forest
+            || self.old_forest > self.new_forest
+            || self.paths.len() != old_peaks.num_peaks()
+        {
+            return false;
+        }
+
+        // the leaves of the old trees are consecutive, starting with the largest tree
+        let mut start = 0;
+        let old_trees = TrueBitPositionIterator::new(self.old_forest).rev();
+        for ((tree_bit, old_peak), path) in old_trees.zip(old_peaks.peaks()).zip(&self.paths) {
+            // find the new tree containing the old one, `start` is part of the new forest since
+            // the old forest is not greater than the new one
+            let new_tree_bit = leaf_to_corresponding_tree(start, self.new_forest)
+                .expect("position must be part of the forest");
+            if path.depth() as u32 + tree_bit != new_tree_bit {
+                return false;
+            }
+
+            // locate the old tree in the new tree, and the new tree in the list of peaks
+            let forest_before = self.new_forest & high_bitmask(new_tree_bit + 1);
+            let index = (start - forest_before) >> tree_bit;
+            let new_peak = &new_peaks.peaks()[forest_before.count_ones() as usize];
+            if !path.verify(index as u64, *old_peak, new_peak) {
+                return false;
+            }
+
+            start += 1 << tree_bit;
+        }
+
+        true
+    }
+}
@@ -13,10 +13,23 @@
 use super::{
     super::{InnerNodeInfo, MerklePath},
     bit::TrueBitPositionIterator,
-    leaf_to_corresponding_tree, nodes_in_forest, MmrDelta, MmrError, MmrPeaks, MmrProof, Rpo256,
-    RpoDigest,
+    leaf_to_corresponding_tree, nodes_in_forest, MmrConsistencyProof, MmrDelta, MmrError, MmrPeaks,
+    MmrProof, PartialMmr, Rpo256, RpoDigest,
 };
-use alloc::vec::Vec;
+use crate::utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+use alloc::{string::ToString, vec::Vec};
+use core::ops::Range;
+
+// CONSTANTS
+// ================================================================================================
+
+/// Version of the serialization format of [Mmr].
+const MMR_SERIALIZATION_VERSION: u8 = 1;
+
+/// Number of leaves of the subtrees built sequentially when appending elements to an [Mmr] using
+/// multiple threads.
+#[cfg(feature = "rayon")]
+const SEQUENTIAL_SUBTREE_LEAVES: usize = 1 << 8;
 
 // MMR
 // ===============================================================================================
@@ -26,8 +39,11 @@ use alloc::vec::Vec;
 ///
 /// Since this is a full representation of the MMR, elements are never removed and the MMR will
 /// grow roughly `O(2n)` in number of leaf elements.
+///
+/// To keep adding elements to an MMR without holding its nodes, see [PartialMmr::from_peaks].
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "MmrParts"))]
 pub struct Mmr {
     /// Refer to the `forest` method documentation for details of the semantics of this value.
     pub(super) forest: usize,
@@ -39,6 +55,12 @@ pub struct Mmr {
     /// also means new elements can be added to the forest, and merging of trees is very cheap with
     /// no need to copy elements.
     pub(super) nodes: Vec<RpoDigest>,
+
+    /// The peaks of the current version of the forest, updated as elements are added.
+    ///
+    /// The peaks are derived from the nodes, so they are not serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(super) peaks: MmrPeaks,
 }
 
 impl Default for Mmr {
@@ -53,7 +75,8 @@ impl Mmr {
 
     /// Constructor for an empty `Mmr`.
     pub fn new() -> Mmr {
-        Mmr { forest: 0, nodes: Vec::new() }
+        let peaks = MmrPeaks::new(0, Vec::new()).expect("an empty MMR has no peaks");
+        Mmr { forest: 0, nodes: Vec::new(), peaks }
     }
 
     // ACCESSORS
@@ -69,16 +92,44 @@ impl Mmr {
         self.forest
     }
 
+    /// Returns the peaks of the current version of the MMR.
+    ///
+    /// The peaks are maintained as elements are added, so this does not require any computation.
+    pub fn latest_peaks(&self) -> &MmrPeaks {
+        &self.peaks
+    }
+
+    /// Returns the hash of the peaks of the current version of the MMR, as computed by
+    /// [MmrPeaks::hash_peaks].
+    ///
+    /// The hash is computed from the maintained peaks on every call, so that adding elements to
+    /// the MMR does not pay for hashing peaks which are never used.
+    pub fn latest_peaks_hash(&self) -> RpoDigest {
+        self.peaks.hash_peaks()
+    }
+
     // FUNCTIONALITY
     // ============================================================================================
 
-    /// Given a leaf position, returns the Merkle path to its corresponding peak. If the position
-    /// is greater-or-equal than the tree size an error is returned.
+    /// Given a leaf position, returns the Merkle path to its corresponding peak in the version of
+    /// the MMR specified by `target_forest`. If the position is greater-or-equal than the tree
+    /// size an error is returned.
+    ///
+    /// The returned proof verifies against the peaks of that version, as returned by
+    /// [Mmr::peaks], which allows proving that a leaf was part of an earlier state of the MMR.
     ///
     /// Note: The leaf position is the 0-indexed number corresponding to the order the leaves were
     /// added, this corresponds to the MMR size _prior_ to adding the element. So the 1st element
     /// has position 0, the second position 1, and so on.
+    ///
+    /// # Errors
+    /// Returns an error if `target_forest` is greater than the current forest, or if the position
+    /// is not part of the MMR at `target_forest`.
     pub fn open(&self, pos: usize, target_forest: usize) -> Result<MmrProof, MmrError> {
+        if target_forest > self.forest {
+            return Err(MmrError::InvalidPeaks);
+        }
+
         // find the target tree responsible for the MMR position
         let tree_bit =
             leaf_to_corresponding_tree(pos, target_forest).ok_or(MmrError::InvalidPosition(pos))?;
@@ -134,21 +185,66 @@ impl Mmr {
         let mut left_offset = self.nodes.len().saturating_sub(2);
         let mut right = el;
         let mut left_tree = 1;
+        let mut num_merged = 0;
         while self.forest & left_tree != 0 {
             right = Rpo256::merge(&[self.nodes[left_offset], right]);
             self.nodes.push(right);
 
             left_offset = left_offset.saturating_sub(nodes_in_forest(left_tree));
             left_tree <<= 1;
+            num_merged += 1;
         }
 
         self.forest += 1;
+        self.peaks.add_tree(1, num_merged, right);
+    }
+
+    /// Adds the provided elements to the MMR, returning the range of positions assigned to them.
+    ///
+    /// The space for all the new nodes is reserved upfront. Note that the resulting MMR is the
+    /// same as adding the elements one by one, which already computes every new inner node
+    /// exactly once, as trees of the same size are merged bottom-up.
+    ///
+    /// With the `rayon` feature, the elements are split into the largest perfect trees which can
+    /// be appended to the forest, which are built using multiple threads before being merged with
+    /// the existing trees.
+    pub fn append_many(&mut self, elements: impl IntoIterator<Item = RpoDigest>) -> Range<usize> {
+        let elements: Vec<RpoDigest> = elements.into_iter().collect();
+        let start = self.forest;
+        let end = start + elements.len();
+
+        self.nodes.reserve(nodes_in_forest(end) - self.nodes.len());
+
+        #[cfg(not(feature = "rayon"))]
+        for el in elements {
+            self.add(el);
+        }
+
+        #[cfg(feature = "rayon")]
+        {
+            let mut remaining = elements.as_slice();
+            while !remaining.is_empty() {
+                // a tree can only be appended at a position aligned to its size, otherwise its
+                // leaves would be split across the trees of the forest
+                let max_size =
+                    1_usize.checked_shl(self.forest.trailing_zeros()).unwrap_or(usize::MAX);
+                let size = max_size.min(1 << remaining.len().ilog2());
+
+                let (leaves, rest) = remaining.split_at(size);
+                self.add_tree(leaves);
+                remaining = rest;
+            }
+        }
+
+        start..end
     }
 
     /// Returns an peaks of the MMR for the version specified by `forest`.
     pub fn peaks(&self, forest: usize) -> Result<MmrPeaks, MmrError> {
         if forest > self.forest {
             return Err(MmrError::InvalidPeaks);
+        } else if forest == self.forest {
+            return Ok(self.peaks.clone());
         }
 
         let peaks: Vec<RpoDigest> = TrueBitPositionIterator::new(forest)
@@ -241,19 +337,175 @@ impl Mmr {
         Ok(MmrDelta { forest: to_forest, data: result })
     }
 
+    /// Returns a proof that the version of the MMR specified by `new_forest` is an append-only
+    /// extension of the version specified by `old_forest`.
+    ///
+    /// # Errors
+    /// Returns an error if `new_forest` is greater than the current forest, or if `old_forest` is
+    /// greater than `new_forest`.
+    pub fn consistency_proof(
+        &self,
+        old_forest: usize,
+        new_forest: usize,
+    ) -> Result<MmrConsistencyProof, MmrError> {
+        if new_forest > self.forest || old_forest > new_forest {
+            return Err(MmrError::InvalidPeaks);
+        }
+
+        // the path of an old peak is the upper part of the path of the first leaf of its tree
+        let mut paths = Vec::with_capacity(old_forest.count_ones() as usize);
+        let mut start = 0;
+        for tree_bit in TrueBitPositionIterator::new(old_forest).rev() {
+            let proof = self.open(start, new_forest)?;
+            paths.push(MerklePath::new(proof.merkle_path[tree_bit as usize..].to_vec()));
+            start += 1 << tree_bit;
+        }
+
+        Ok(MmrConsistencyProof { old_forest, new_forest, paths })
+    }
+
+    /// Prunes this MMR down to its peaks and the authentication paths of the leaves at the
+    /// positions in `keep`, returning the result as a [PartialMmr].
+    ///
+    /// All the other nodes of the MMR are dropped. The resulting [PartialMmr] can open the kept
+    /// leaves and keep their authentication paths up to date as new leaves are added. Note that
+    /// the values of the kept leaves themselves are not retained, and must be stored separately.
+    ///
+    /// # Errors
+    /// Returns an error if a position in `keep` is not part of the MMR.
+    pub fn prune(self, keep: &[usize]) -> Result<PartialMmr, MmrError> {
+        let mut partial = PartialMmr::from_peaks(self.peaks(self.forest)?);
+        for &pos in keep {
+            let leaf = self.get(pos)?;
+            let proof = self.open(pos, self.forest)?;
+            partial.track(pos, leaf, &proof.merkle_path)?;
+        }
+
+        Ok(partial)
+    }
+
     /// An iterator over inner nodes in the MMR. The order of iteration is unspecified.
     pub fn inner_nodes(&self) -> MmrNodes {
         MmrNodes {
             mmr: self,
+            target_forest: self.forest,
             forest: 0,
             last_right: 0,
             index: 0,
         }
     }
 
+    /// An iterator over inner nodes in the version of the MMR specified by `forest`. The order of
+    /// iteration is unspecified.
+    ///
+    /// This allows populating a [MerkleStore](crate::merkle::MerkleStore) with the nodes of an
+    /// earlier state of the MMR.
+    ///
+    /// # Errors
+    /// Returns an error if `forest` is greater than the current forest.
+    pub fn inner_nodes_at(&self, forest: usize) -> Result<MmrNodes, MmrError> {
+        if forest > self.forest {
+            return Err(MmrError::InvalidPeaks);
+        }
+
+        Ok(MmrNodes {
+            mmr: self,
+            target_forest: forest,
+            forest: 0,
+            last_right: 0,
+            index: 0,
+        })
+    }
+
+    /// Returns an iterator over the leaves of the MMR together with their positions, in the order
+    /// in which they were added.
+    pub fn leaves(&self) -> impl Iterator<Item = (usize, RpoDigest)> + '_ {
+        self.leaves_in(0..self.forest)
+    }
+
+    /// Returns an iterator over the leaves at the positions in `range`, together with their
+    /// positions, in the order in which they were added.
+    ///
+    /// # Errors
+    /// Returns an error if the range ends after the last leaf of the MMR.
+    pub fn leaves_range(
+        &self,
+        range: Range<usize>,
+    ) -> Result<impl Iterator<Item = (usize, RpoDigest)> + '_, MmrError> {
+        if range.end > self.forest {
+            return Err(MmrError::InvalidPosition(range.end - 1));
+        }
+
+        Ok(self.leaves_in(range))
+    }
+
     // UTILITIES
     // ============================================================================================
 
+    /// Returns the MMR with the provided number of leaves and nodes, checking that the nodes are
+    /// the ones obtained by adding the leaves to an empty MMR.
+    fn from_parts(forest: usize, nodes: Vec<RpoDigest>) -> Result<Self, DeserializationError> {
+        if forest > usize::MAX / 2 || nodes_in_forest(forest) != nodes.len() {
+            return Err(DeserializationError::InvalidValue(format!(
+                "expected {forest} leaves to be stored in {} nodes",
+                nodes.len()
+            )));
+        }
+
+        // Replay the additions of the leaves, which are located in the node vector at the length
+        // of the MMR prior to adding them, and check that the inner nodes match
+        let mut mmr = Mmr::new();
+        mmr.nodes.reserve(nodes.len());
+        while mmr.nodes.len() < nodes.len() {
+            mmr.add(nodes[mmr.nodes.len()]);
+        }
+        if mmr.nodes != nodes {
+            return Err(DeserializationError::InvalidValue(
+                "the inner nodes of the Mmr do not match its leaves".to_string(),
+            ));
+        }
+
+        Ok(mmr)
+    }
+
+    /// Returns an iterator over the leaves at the positions in `range`, which must be part of the
+    /// MMR.
+    ///
+    /// A leaf is appended to the nodes when it is added to the MMR, so the leaf at position `pos`
+    /// is located right after the nodes of the forest with `pos` leaves.
+    fn leaves_in(&self, range: Range<usize>) -> impl Iterator<Item = (usize, RpoDigest)> + '_ {
+        range.map(|pos| (pos, self.nodes[nodes_in_forest(pos)]))
+    }
+
+    /// Adds a perfect tree with the provided leaves to the MMR, merging it with the existing trees
+    /// of the same size.
+    ///
+    /// The number of leaves must be a power of two, and the forest must be a multiple of it.
+    #[cfg(feature = "rayon")]
+    fn add_tree(&mut self, leaves: &[RpoDigest]) {
+        debug_assert!(leaves.len().is_power_of_two() && self.forest % leaves.len() == 0);
+
+        let offset = self.nodes.len();
+        self.nodes.resize(offset + nodes_in_forest(leaves.len()), RpoDigest::default());
+        build_tree(leaves, &mut self.nodes[offset..]);
+
+        // same as for a single element in `add`, but starting with a tree of `leaves.len()`
+        let mut right = self.nodes[self.nodes.len() - 1];
+        let mut right_tree = leaves.len();
+        let mut num_merged = 0;
+        while self.forest & right_tree != 0 {
+            let left = self.nodes[self.nodes.len() - 1 - nodes_in_forest(right_tree)];
+            right = Rpo256::merge(&[left, right]);
+            self.nodes.push(right);
+
+            right_tree <<= 1;
+            num_merged += 1;
+        }
+
+        self.forest += leaves.len();
+        self.peaks.add_tree(leaves.len(), num_merged, right);
+    }
+
     /// Internal function used to collect the Merkle path of a value.
     ///
     /// The arguments are relative to the target tree. To compute the opening of the second leaf
@@ -310,6 +562,37 @@ impl Mmr {
     }
 }
 
+// TREE CONSTRUCTION
+// ================================================================================================
+
+/// Writes the nodes of the perfect tree with the provided leaves into `nodes`, in postorder.
+///
+/// The subtrees of the tree are built in parallel, down to subtrees of
+/// [SEQUENTIAL_SUBTREE_LEAVES] leaves which are built sequentially.
+#[cfg(feature = "rayon")]
+fn build_tree(leaves: &[RpoDigest], nodes: &mut [RpoDigest]) {
+    debug_assert!(leaves.len().is_power_of_two() && nodes.len() == nodes_in_forest(leaves.len()));
+
+    if leaves.len() <= SEQUENTIAL_SUBTREE_LEAVES {
+        let mut mmr = Mmr::new();
+        for &leaf in leaves {
+            mmr.add(leaf);
+        }
+        nodes.copy_from_slice(&mmr.nodes);
+        return;
+    }
+
+    // in postorder, the nodes of the left subtree are followed by the nodes of the right subtree
+    // and then by the root
+    let (left_leaves, right_leaves) = leaves.split_at(leaves.len() / 2);
+    let (children, root) = nodes.split_at_mut(nodes.len() - 1);
+    let (left_nodes, right_nodes) = children.split_at_mut(children.len() / 2);
+    rayon::join(|| build_tree(left_leaves, left_nodes), || build_tree(right_leaves, right_nodes));
+
+    root[0] =
+        Rpo256::merge(&[left_nodes[left_nodes.len() - 1], right_nodes[right_nodes.len() - 1]]);
+}
+
 // CONVERSIONS
 // ================================================================================================
 
@@ -319,20 +602,81 @@ where
 {
     fn from(values: T) -> Self {
         let mut mmr = Mmr::new();
-        for v in values {
-            mmr.add(v)
-        }
+        mmr.append_many(values);
         mmr
     }
 }
 
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for Mmr {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u8(MMR_SERIALIZATION_VERSION);
+
+        // Write the number of leaves, followed by every node of the forest in postorder
+        target.write_usize(self.forest);
+        target.write_usize(self.nodes.len());
+        for node in self.nodes.iter() {
+            node.write_into(target);
+        }
+    }
+}
+
+impl Deserializable for Mmr {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let version = source.read_u8()?;
+        if version != MMR_SERIALIZATION_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "unsupported Mmr serialization version {version}"
+            )));
+        }
+
+        let forest = source.read_usize()?;
+        let num_nodes = source.read_usize()?;
+        if forest > usize::MAX / 2 || nodes_in_forest(forest) != num_nodes {
+            return Err(DeserializationError::InvalidValue(format!(
+                "expected {forest} leaves to be stored in {num_nodes} nodes"
+            )));
+        }
+
+        // the number of nodes is not trusted, so the nodes are read one by one instead of
+        // reserving space for all of them upfront
+        let mut nodes = Vec::new();
+        for _ in 0..num_nodes {
+            nodes.push(RpoDigest::read_from(source)?);
+        }
+
+        Self::from_parts(forest, nodes)
+    }
+}
+
+/// The serialized fields of an [Mmr], from which its peaks are recomputed on deserialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct MmrParts {
+    forest: usize,
+    nodes: Vec<RpoDigest>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<MmrParts> for Mmr {
+    type Error = DeserializationError;
+
+    fn try_from(parts: MmrParts) -> Result<Self, Self::Error> {
+        Self::from_parts(parts.forest, parts.nodes)
+    }
+}
+
 // ITERATOR
 // ===============================================================================================
 
 /// Yields inner nodes of the [Mmr].
 pub struct MmrNodes<'a> {
-    /// [Mmr] being yielded, when its `forest` value is matched, the iterations is finished.
+    /// [Mmr] being yielded.
     mmr: &'a Mmr,
+    /// Version of the [Mmr] being yielded, when this value is matched, the iterations is finished.
+    target_forest: usize,
     /// Keeps track of the left nodes yielded so far waiting for a right pair, this matches the
     /// semantics of the [Mmr]'s forest attribute, since that too works as a buffer of left nodes
     /// waiting for a pair to be hashed together.
@@ -351,7 +695,7 @@ impl<'a> Iterator for MmrNodes<'a> {
         debug_assert!(self.last_right.count_ones() <= 1, "last_right tracks zero or one element");
 
         // only parent nodes are emitted, remove the single node tree from the forest
-        let target = self.mmr.forest & (usize::MAX << 1);
+        let target = self.target_forest & (usize::MAX << 1);
 
         if self.forest < target {
             if self.last_right == 0 {
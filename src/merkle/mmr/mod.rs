@@ -1,4 +1,5 @@
 mod bit;
+mod consistency;
 mod delta;
 mod error;
 mod full;
@@ -14,6 +15,7 @@ use super::{Felt, Rpo256, RpoDigest, Word};
 
 // REEXPORTS
 // ================================================================================================
+pub use consistency::MmrConsistencyProof;
 pub use delta::MmrDelta;
 pub use error::MmrError;
 pub use full::Mmr;
@@ -72,6 +72,10 @@ impl PartialMmr {
     // --------------------------------------------------------------------------------------------
 
     /// Returns a new [PartialMmr] instantiated from the specified peaks.
+    ///
+    /// The returned partial MMR does not track any leaf. This allows resuming an MMR from its
+    /// peaks alone: new leaves can be added with [PartialMmr::add], and the peaks are kept up to
+    /// date, but the leaves added before the peaks were taken cannot be opened.
     pub fn from_peaks(peaks: MmrPeaks) -> Self {
         let forest = peaks.num_leaves();
         let peaks = peaks.into();
@@ -866,6 +870,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_partial_mmr_resume_from_peaks() {
+        let mut mmr: Mmr = (0..11).map(int_to_node).collect::<Vec<_>>().into();
+        let mut partial_mmr = PartialMmr::from_peaks(mmr.peaks(mmr.forest()).unwrap());
+
+        for el in (11..64).map(int_to_node) {
+            mmr.add(el);
+            partial_mmr.add(el, false);
+            assert_eq!(mmr.peaks(mmr.forest()).unwrap(), partial_mmr.peaks());
+        }
+
+        // the leaves added before resuming cannot be opened
+        assert_eq!(partial_mmr.open(0).unwrap(), None);
+    }
+
     #[test]
     fn test_partial_mmr_add_with_track() {
         let mut mmr = Mmr::default();
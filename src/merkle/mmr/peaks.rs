@@ -1,5 +1,8 @@
-use super::{super::ZERO, Felt, MmrError, MmrProof, Rpo256, RpoDigest, Word};
-use alloc::vec::Vec;
+use super::{
+    super::{path::VerifiedNodes, NodeIndex, ZERO},
+    leaf_to_corresponding_tree, Felt, MmrError, MmrProof, Rpo256, RpoDigest, Word,
+};
+use alloc::{collections::BTreeMap, vec::Vec};
 
 // MMR PEAKS
 // ================================================================================================
@@ -83,11 +86,70 @@ impl MmrPeaks {
         Rpo256::hash_elements(&self.flatten_and_pad_peaks())
     }
 
+    /// Returns a commitment to both the peaks and the number of leaves of the underlying MMR.
+    ///
+    /// The commitment is computed by merging the result of [MmrPeaks::hash_peaks] with a digest
+    /// holding the number of leaves, i.e. `merge(hash_peaks, [num_leaves, 0, 0, 0])`. Unlike the
+    /// hash of the peaks alone, it binds the commitment to the size of the MMR, so that it cannot
+    /// be replayed as the commitment of an MMR with a different number of leaves.
+    pub fn commitment(&self) -> RpoDigest {
+        let num_leaves = RpoDigest::new([Felt::new(self.num_leaves as u64), ZERO, ZERO, ZERO]);
+        Rpo256::merge(&[self.hash_peaks(), num_leaves])
+    }
+
+    /// Returns true if `commitment` is the commitment to these peaks and their number of leaves,
+    /// as computed by [MmrPeaks::commitment].
+    pub fn verify_commitment(&self, commitment: RpoDigest) -> bool {
+        self.commitment() == commitment
+    }
+
+    /// Returns true if `opening` proves that `value` is a leaf of the MMR with these peaks.
+    ///
+    /// Openings created for a different version of the MMR, or for a position which is not part
+    /// of the MMR, are rejected.
     pub fn verify(&self, value: RpoDigest, opening: MmrProof) -> bool {
+        if opening.forest != self.num_leaves || opening.position >= opening.forest {
+            return false;
+        }
+
         let root = &self.peaks[opening.peak_index()];
         opening.merkle_path.verify(opening.relative_pos() as u64, value, root)
     }
 
+    /// Returns true if every opening in `items` proves that the associated value is a leaf of the
+    /// MMR with these peaks.
+    ///
+    /// The openings of each tree of the MMR are verified as by
+    /// [verify_paths](crate::merkle::verify_paths), so the nodes they share are hashed only once.
+    ///
+    /// Openings created for a different version of the MMR, for a position which is not part of
+    /// the MMR, or whose path length does not match the depth of the tree, are rejected.
+    pub fn verify_many(&self, items: &[(RpoDigest, MmrProof)]) -> bool {
+        // nodes which have been verified so far, for each of the peaks
+        let mut verified: BTreeMap<usize, VerifiedNodes> = BTreeMap::new();
+
+        for (value, opening) in items {
+            if opening.forest != self.num_leaves {
+                return false;
+            }
+            let Some(tree_depth) = leaf_to_corresponding_tree(opening.position, opening.forest)
+            else {
+                return false;
+            };
+
+            let peak_index = opening.peak_index();
+            let index = NodeIndex::new_unchecked(tree_depth as u8, opening.relative_pos() as u64);
+            let tree = verified
+                .entry(peak_index)
+                .or_insert_with(|| VerifiedNodes::new(self.peaks[peak_index]));
+            if !tree.verify(index, *value, &opening.merkle_path) {
+                return false;
+            }
+        }
+
+        true
+    }
+
     /// Flattens and pads the peaks to make hashing inside of the Miden VM easier.
     ///
     /// The procedure will:
@@ -129,6 +191,19 @@ impl MmrPeaks {
         elements.resize(len, ZERO);
         elements
     }
+
+    // STATE MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Updates these peaks after a tree with `num_leaves` leaves was added to the underlying MMR
+    /// and merged with the last `num_merged` trees, resulting in a tree with root `peak`.
+    pub(super) fn add_tree(&mut self, num_leaves: usize, num_merged: usize, peak: RpoDigest) {
+        self.peaks.truncate(self.peaks.len() - num_merged);
+        self.peaks.push(peak);
+        self.num_leaves += num_leaves;
+
+        debug_assert_eq!(self.num_leaves.count_ones() as usize, self.peaks.len());
+    }
 }
 
 impl From<MmrPeaks> for Vec<RpoDigest> {
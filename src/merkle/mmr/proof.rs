@@ -1,5 +1,5 @@
 /// The representation of a single Merkle path.
-use super::super::MerklePath;
+use super::super::{MerklePath, NodeIndex, RpoDigest, ValuePath};
 use super::{full::high_bitmask, leaf_to_corresponding_tree};
 
 // MMR PROOF
@@ -37,6 +37,24 @@ impl MmrProof {
         let num_smaller_peaks = (self.forest & smaller_peak_mask).count_ones();
         (self.forest.count_ones() - num_smaller_peaks - 1) as usize
     }
+
+    /// Returns the index of the leaf in the tree of the peak against which this proof can be
+    /// verified.
+    ///
+    /// Together with [MmrProof::into_value_path], this allows verifying the proof as a regular
+    /// Merkle opening against that peak, e.g. with [MerklePath::verify] or a
+    /// [MerkleStore](crate::merkle::MerkleStore) holding the nodes of the MMR.
+    pub fn node_index(&self) -> NodeIndex {
+        let tree_bit = leaf_to_corresponding_tree(self.position, self.forest)
+            .expect("position must be part of the forest");
+        NodeIndex::new_unchecked(tree_bit as u8, self.relative_pos() as u64)
+    }
+
+    /// Converts this proof into a [ValuePath] opening `value` in the tree of the peak against
+    /// which this proof can be verified.
+    pub fn into_value_path(self, value: RpoDigest) -> ValuePath {
+        ValuePath::new(value, self.merkle_path)
+    }
 }
 
 // TESTS
@@ -2,11 +2,13 @@ use super::{
     super::{InnerNodeInfo, Rpo256, RpoDigest},
     bit::TrueBitPositionIterator,
     full::high_bitmask,
-    leaf_to_corresponding_tree, nodes_in_forest, Mmr, MmrPeaks, PartialMmr,
+    leaf_to_corresponding_tree, nodes_in_forest, Mmr, MmrConsistencyProof, MmrError, MmrPeaks,
+    PartialMmr,
 };
 use crate::{
-    merkle::{int_to_node, InOrderIndex, MerklePath, MerkleTree, MmrProof, NodeIndex},
-    Felt, Word,
+    merkle::{int_to_node, InOrderIndex, MerklePath, MerkleStore, MerkleTree, MmrProof, NodeIndex},
+    utils::{ByteWriter, Deserializable, Serializable},
+    Felt, Word, ZERO,
 };
 use alloc::vec::Vec;
 
@@ -337,6 +339,240 @@ fn test_mmr_open_older_version() {
     }
 }
 
+#[test]
+fn test_mmr_open_verifies_against_older_peaks() {
+    let mmr: Mmr = LEAVES.into();
+
+    // every leaf of every earlier version verifies against the peaks of that version
+    for forest in 1..=mmr.forest() {
+        let peaks = mmr.peaks(forest).unwrap();
+        for pos in 0..forest {
+            let proof = mmr.open(pos, forest).unwrap();
+            assert!(peaks.verify(LEAVES[pos], proof));
+        }
+        assert_eq!(mmr.open(forest, forest), Err(MmrError::InvalidPosition(forest)));
+    }
+
+    // versions after the current one cannot be opened
+    assert_eq!(mmr.open(0, mmr.forest() + 1), Err(MmrError::InvalidPeaks));
+}
+
+#[test]
+fn test_mmr_proof_into_value_path() {
+    let mmr: Mmr = LEAVES.into();
+    let peaks = mmr.peaks(mmr.forest()).unwrap();
+    let store: MerkleStore = (&mmr).into();
+
+    for (pos, leaf) in LEAVES.iter().enumerate() {
+        let proof = mmr.open(pos, mmr.forest()).unwrap();
+        let peak = peaks.peaks()[proof.peak_index()];
+        let index = proof.node_index();
+        assert_eq!(index.depth(), proof.merkle_path.depth());
+
+        let value_path = proof.into_value_path(*leaf);
+        assert!(value_path.path.verify(index.value(), value_path.value, &peak));
+
+        // the peak of a tree with a single leaf is not an inner node, so it is not in the store
+        if index.depth() > 0 {
+            assert_eq!(store.get_path(peak, index).unwrap(), value_path);
+        }
+    }
+}
+
+#[test]
+fn test_mmr_append_many() {
+    let mut mmr: Mmr = LEAVES[..3].iter().copied().into();
+    assert_eq!(mmr.append_many(LEAVES[3..].iter().copied()), 3..LEAVES.len());
+
+    let expected: Mmr = LEAVES.into();
+    assert_eq!(mmr.forest(), expected.forest());
+    assert_eq!(mmr.nodes, expected.nodes);
+
+    // appending nothing leaves the MMR unchanged
+    assert_eq!(mmr.append_many([]), LEAVES.len()..LEAVES.len());
+    assert_eq!(mmr.nodes, expected.nodes);
+}
+
+#[test]
+fn test_mmr_append_many_large() {
+    // enough elements for the trees to be split when built using multiple threads
+    let elements: Vec<RpoDigest> = (0..2000).map(int_to_node).collect();
+
+    let mut mmr: Mmr = elements[..5].iter().copied().into();
+    assert_eq!(mmr.append_many(elements[5..].iter().copied()), 5..elements.len());
+
+    let mut expected = Mmr::new();
+    for el in elements.iter() {
+        expected.add(*el);
+    }
+    assert_eq!(mmr.forest(), expected.forest());
+    assert_eq!(mmr.nodes, expected.nodes);
+
+    // the maintained peaks match the ones computed from the nodes
+    expected.add(int_to_node(2000));
+    assert_eq!(mmr.latest_peaks(), &expected.peaks(mmr.forest()).unwrap());
+}
+
+#[test]
+fn test_mmr_latest_peaks() {
+    let full: Mmr = LEAVES.into();
+
+    let mut mmr = Mmr::new();
+    assert_eq!(mmr.latest_peaks(), &full.peaks(0).unwrap());
+    assert_eq!(mmr.latest_peaks_hash(), full.peaks(0).unwrap().hash_peaks());
+    for (pos, leaf) in LEAVES.iter().enumerate() {
+        mmr.add(*leaf);
+        assert_eq!(mmr.latest_peaks(), &full.peaks(pos + 1).unwrap());
+        assert_eq!(mmr.peaks(mmr.forest()).unwrap(), full.peaks(pos + 1).unwrap());
+        assert_eq!(mmr.latest_peaks_hash(), full.peaks(pos + 1).unwrap().hash_peaks());
+    }
+
+    // the hash is also maintained by batched additions and rebuilt on deserialization
+    assert_eq!(full.latest_peaks_hash(), mmr.latest_peaks_hash());
+    let decoded = Mmr::read_from_bytes(&full.to_bytes()).unwrap();
+    assert_eq!(decoded.latest_peaks(), full.latest_peaks());
+    assert_eq!(decoded.latest_peaks_hash(), full.latest_peaks_hash());
+}
+
+#[test]
+fn test_mmr_consistency_proof() {
+    let mmr: Mmr = LEAVES.into();
+
+    for new_forest in 0..=LEAVES.len() {
+        let new_peaks = mmr.peaks(new_forest).unwrap();
+        for old_forest in 0..=new_forest {
+            let old_peaks = mmr.peaks(old_forest).unwrap();
+            let proof = mmr.consistency_proof(old_forest, new_forest).unwrap();
+            assert!(proof.verify(&old_peaks, &new_peaks));
+        }
+    }
+
+    // a rewritten history is detected
+    let proof = mmr.consistency_proof(3, 7).unwrap();
+    let mut leaves = LEAVES;
+    leaves[1] = int_to_node(100);
+    let rewritten: Mmr = leaves.into();
+    assert!(!proof.verify(&rewritten.peaks(3).unwrap(), &mmr.peaks(7).unwrap()));
+    assert!(!proof.verify(&mmr.peaks(3).unwrap(), &rewritten.peaks(7).unwrap()));
+
+    // proofs are bound to the versions they were generated for, and must have a path per peak
+    assert!(!proof.verify(&mmr.peaks(2).unwrap(), &mmr.peaks(7).unwrap()));
+    assert!(!proof.verify(&mmr.peaks(3).unwrap(), &mmr.peaks(6).unwrap()));
+    let mut paths = proof.paths.clone();
+    paths.pop();
+    let proof = MmrConsistencyProof { paths, ..proof };
+    assert!(!proof.verify(&mmr.peaks(3).unwrap(), &mmr.peaks(7).unwrap()));
+
+    assert!(mmr.consistency_proof(4, 3).is_err());
+    assert!(mmr.consistency_proof(3, LEAVES.len() + 1).is_err());
+}
+
+#[test]
+fn test_mmr_prune() {
+    let mmr: Mmr = LEAVES.into();
+    let partial = mmr.clone().prune(&[1, 6]).unwrap();
+
+    assert_eq!(partial.peaks(), mmr.peaks(mmr.forest()).unwrap());
+    for pos in 0..LEAVES.len() {
+        let expected = [1, 6].contains(&pos).then(|| mmr.open(pos, mmr.forest()).unwrap());
+        assert_eq!(partial.open(pos).unwrap(), expected);
+    }
+
+    // positions which are not part of the MMR cannot be kept
+    assert_eq!(mmr.prune(&[LEAVES.len()]).err(), Some(MmrError::InvalidPosition(LEAVES.len())));
+}
+
+#[test]
+fn test_mmr_leaves() {
+    let mmr: Mmr = LEAVES.into();
+
+    let leaves: Vec<(usize, RpoDigest)> = mmr.leaves().collect();
+    assert_eq!(leaves, LEAVES.iter().copied().enumerate().collect::<Vec<_>>());
+
+    let range: Vec<(usize, RpoDigest)> = mmr.leaves_range(2..5).unwrap().collect();
+    assert_eq!(range, leaves[2..5]);
+    assert_eq!(mmr.leaves_range(3..3).unwrap().count(), 0);
+    assert_eq!(mmr.leaves_range(0..LEAVES.len()).unwrap().count(), LEAVES.len());
+
+    // ranges ending after the last leaf are rejected
+    assert_eq!(
+        mmr.leaves_range(5..LEAVES.len() + 1).err(),
+        Some(MmrError::InvalidPosition(LEAVES.len()))
+    );
+}
+
+#[test]
+fn test_mmr_serialization() {
+    for num_leaves in 0..LEAVES.len() {
+        let mmr: Mmr = LEAVES[..num_leaves].iter().copied().into();
+        let bytes = mmr.to_bytes();
+        let deserialized = Mmr::read_from_bytes(&bytes).unwrap();
+        assert_eq!(deserialized.forest(), mmr.forest());
+        assert_eq!(deserialized.nodes, mmr.nodes);
+    }
+
+    // tampering with an inner node is detected; the root of the first tree is the 7th of 11 nodes
+    let mmr: Mmr = LEAVES.into();
+    let mut bytes = mmr.to_bytes();
+    let offset = bytes.len() - 5 * 32;
+    bytes[offset] ^= 1;
+    assert!(Mmr::read_from_bytes(&bytes).is_err());
+
+    // a node count inconsistent with the number of leaves is rejected
+    let mut mmr: Mmr = LEAVES.into();
+    mmr.nodes.pop();
+    assert!(Mmr::read_from_bytes(&mmr.to_bytes()).is_err());
+
+    // unknown versions are rejected
+    let mut bytes = Mmr::new().to_bytes();
+    bytes[0] += 1;
+    assert!(Mmr::read_from_bytes(&bytes).is_err());
+
+    // a huge number of nodes is rejected once the input runs out, without reserving space for them
+    let forest = usize::MAX / 2;
+    let mut bytes = Mmr::new().to_bytes()[..1].to_vec();
+    bytes.write_usize(forest);
+    bytes.write_usize(nodes_in_forest(forest));
+    assert!(Mmr::read_from_bytes(&bytes).is_err());
+}
+
+#[test]
+fn test_mmr_peaks_verify_many() {
+    let mmr: Mmr = LEAVES.into();
+    let peaks = mmr.peaks(mmr.forest()).unwrap();
+
+    let mut items: Vec<(RpoDigest, MmrProof)> = LEAVES
+        .iter()
+        .enumerate()
+        .map(|(pos, leaf)| (*leaf, mmr.open(pos, mmr.forest()).unwrap()))
+        .collect();
+    assert!(peaks.verify_many(&items));
+    assert!(peaks.verify_many(&[]));
+
+    // a wrong value is detected, even when its path is shared with an already verified opening
+    items[1].0 = int_to_node(100);
+    assert!(!peaks.verify_many(&items));
+
+    // openings of an earlier version of the MMR are rejected
+    let items = [(LEAVES[0], mmr.open(0, 4).unwrap())];
+    assert!(!peaks.verify_many(&items));
+}
+
+#[test]
+fn test_mmr_peaks_verify_rejects_other_versions() {
+    let mmr: Mmr = LEAVES.into();
+    let peaks = mmr.peaks(mmr.forest()).unwrap();
+
+    // an opening of an earlier version does not verify against the current peaks
+    let proof = mmr.open(0, 4).unwrap();
+    assert!(!peaks.verify(LEAVES[0], proof));
+
+    // an opening of a position outside of the MMR is rejected rather than panicking
+    let mut proof = mmr.open(6, mmr.forest()).unwrap();
+    proof.position = mmr.forest();
+    assert!(!peaks.verify(LEAVES[6], proof));
+}
+
 /// Tests the openings of a simple Mmr with a single tree of depth 8.
 #[test]
 fn test_mmr_open_eight() {
@@ -560,6 +796,20 @@ fn test_mmr_inner_nodes() {
     assert_eq!(postorder, nodes);
 }
 
+#[test]
+fn test_mmr_inner_nodes_at() {
+    let mmr: Mmr = LEAVES.into();
+
+    for forest in 0..=LEAVES.len() {
+        let expected: Mmr = LEAVES[..forest].iter().copied().into();
+        let expected: Vec<InnerNodeInfo> = expected.inner_nodes().collect();
+        let nodes: Vec<InnerNodeInfo> = mmr.inner_nodes_at(forest).unwrap().collect();
+        assert_eq!(nodes, expected);
+    }
+
+    assert!(mmr.inner_nodes_at(LEAVES.len() + 1).is_err());
+}
+
 #[test]
 fn test_mmr_peaks() {
     let mmr: Mmr = LEAVES.into();
@@ -618,6 +868,23 @@ fn test_mmr_hash_peaks() {
     assert_eq!(peaks.hash_peaks(), Rpo256::hash_elements(&digests_to_elements(&expected_peaks)));
 }
 
+#[test]
+fn test_mmr_peaks_commitment() {
+    let mmr: Mmr = LEAVES.into();
+    let peaks = mmr.peaks(mmr.forest()).unwrap();
+
+    let num_leaves = RpoDigest::new([Felt::new(LEAVES.len() as u64), ZERO, ZERO, ZERO]);
+    let expected = Rpo256::merge(&[peaks.hash_peaks(), num_leaves]);
+    assert_eq!(peaks.commitment(), expected);
+    assert!(peaks.verify_commitment(expected));
+
+    // the same peaks for a different number of leaves have the same hash, but not the same
+    // commitment
+    let other = MmrPeaks::new(0b1110, peaks.peaks().to_vec()).unwrap();
+    assert_eq!(other.hash_peaks(), peaks.hash_peaks());
+    assert!(!other.verify_commitment(expected));
+}
+
 #[test]
 fn test_mmr_peaks_hash_less_than_16() {
     let mut peaks = Vec::new();
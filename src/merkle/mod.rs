@@ -15,22 +15,38 @@ mod index;
 pub use index::NodeIndex;
 
 mod merkle_tree;
-pub use merkle_tree::{path_to_text, tree_to_text, MerkleTree};
+pub use merkle_tree::{path_to_text, tree_to_text, MerkleTree, MerkleTreeBuilder, PaddingPolicy};
 
 mod path;
-pub use path::{MerklePath, RootPath, ValuePath};
+pub use path::{verify_paths, MerkleOpening, MerklePath, RootPath, ValuePath};
+
+mod multiproof;
+pub use multiproof::MultiProof;
+
+mod envelope;
+pub use envelope::{
+    EnvelopeProof, HasherKind, ProofEnvelope, ProofEnvelopeError, ProofKind, PROOF_ENVELOPE_VERSION,
+};
 
 mod smt;
 pub use smt::{
-    LeafIndex, SimpleSmt, Smt, SmtLeaf, SmtLeafError, SmtProof, SmtProofError, SMT_DEPTH,
-    SMT_MAX_DEPTH, SMT_MIN_DEPTH,
+    DuplicatePolicy, LeafIndex, SimpleSmt, SimpleSmtBuilder, SimpleSmtMutationSet,
+    SimpleSmtNonMembershipProof, SimpleSmtProof, Smt, SmtLeaf, SmtLeafError, SmtMutationSet,
+    SmtProof, SmtProofError, SmtStoreView, TypedSmt, SMT_DEPTH, SMT_MAX_DEPTH, SMT_MIN_DEPTH,
 };
 
 mod mmr;
-pub use mmr::{InOrderIndex, Mmr, MmrDelta, MmrError, MmrPeaks, MmrProof, PartialMmr};
+pub use mmr::{
+    InOrderIndex, Mmr, MmrConsistencyProof, MmrDelta, MmrError, MmrPeaks, MmrProof, PartialMmr,
+};
+
+mod epoch;
+pub use epoch::{EpochAccumulator, EpochProof};
 
 mod store;
-pub use store::{DefaultMerkleStore, MerkleStore, RecordingMerkleStore, StoreNode};
+pub use store::{
+    dedupe_witnesses, DefaultMerkleStore, MerkleStore, RecordingMerkleStore, StoreNode, WitnessRefs,
+};
 
 mod node;
 pub use node::InnerNodeInfo;
@@ -41,6 +57,29 @@ pub use partial_mt::PartialMerkleTree;
 mod error;
 pub use error::MerkleError;
 
+// CONSTANTS
+// ================================================================================================
+
+/// Maximum depth of a Merkle tree whose nodes can be addressed by a [NodeIndex].
+///
+/// Node positions are 64-bit values, so the deepest level which can be fully addressed has 2^64
+/// nodes.
+pub const MAX_TREE_DEPTH: u8 = 64;
+
+/// Maximum depth of a tree for which the roots of empty subtrees are available via
+/// [EmptySubtreeRoots].
+pub const MAX_EMPTY_SUBTREE_DEPTH: u8 = u8::MAX;
+
+// tie the depth limits of the different data structures together, so they can't drift apart
+const _: () = {
+    assert!(MAX_TREE_DEPTH as u32 == u64::BITS);
+    assert!(MAX_TREE_DEPTH <= MAX_EMPTY_SUBTREE_DEPTH);
+    assert!(SMT_MIN_DEPTH <= SMT_MAX_DEPTH);
+    assert!(SMT_MAX_DEPTH <= MAX_TREE_DEPTH);
+    assert!(SMT_DEPTH <= SMT_MAX_DEPTH);
+    assert!(PartialMerkleTree::MAX_DEPTH <= MAX_TREE_DEPTH);
+};
+
 // HELPER FUNCTIONS
 // ================================================================================================
 
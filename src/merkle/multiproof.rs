@@ -0,0 +1,134 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use super::{MerkleError, NodeIndex, Rpo256, RpoDigest};
+use crate::utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+
+// MULTI PROOF
+// ================================================================================================
+
+/// An opening of multiple nodes of a Merkle tree against a single root.
+///
+/// Instead of carrying one [MerklePath](super::MerklePath) per opened node, a multi-proof carries
+/// the union of all the sibling nodes required to recompute the root, with each node stored only
+/// once. Nodes which can be computed from the opened nodes themselves are omitted.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct MultiProof {
+    nodes: BTreeMap<NodeIndex, RpoDigest>,
+}
+
+impl MultiProof {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [MultiProof] instantiated from the specified auxiliary nodes.
+    pub fn new(nodes: impl IntoIterator<Item = (NodeIndex, RpoDigest)>) -> Self {
+        Self { nodes: nodes.into_iter().collect() }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the number of auxiliary nodes in this proof.
+    pub fn num_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns an iterator over the auxiliary nodes of this proof, sorted by their index.
+    pub fn nodes(&self) -> impl Iterator<Item = (&NodeIndex, &RpoDigest)> {
+        self.nodes.iter()
+    }
+
+    // PROOF VERIFIER
+    // --------------------------------------------------------------------------------------------
+
+    /// Computes the root to which this proof resolves when opened at the specified nodes.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - A node required to compute the root is neither opened nor part of this proof.
+    /// - An opened node conflicts with a node of the proof, or with a node computed from the
+    ///   other openings.
+    pub fn compute_root(
+        &self,
+        openings: impl IntoIterator<Item = (NodeIndex, RpoDigest)>,
+    ) -> Result<RpoDigest, MerkleError> {
+        let mut nodes = self.nodes.clone();
+        for (index, value) in openings {
+            insert_checked(&mut nodes, index, value)?;
+        }
+
+        // nodes are ordered by depth first, so the deepest nodes are always processed first and
+        // every parent is inserted before it is popped
+        while let Some((mut index, value)) = nodes.pop_last() {
+            if index.is_root() {
+                return Ok(value);
+            }
+
+            let sibling = nodes
+                .remove(&index.sibling())
+                .ok_or(MerkleError::NodeNotInSet(index.sibling()))?;
+            let parent = Rpo256::merge(&index.build_node(value, sibling));
+            index.move_up();
+            insert_checked(&mut nodes, index, parent)?;
+        }
+
+        Err(MerkleError::NodeNotInSet(NodeIndex::root()))
+    }
+
+    /// Returns `true` if all the `openings` exist in a Merkle tree with the specified `root`.
+    pub fn verify(
+        &self,
+        openings: impl IntoIterator<Item = (NodeIndex, RpoDigest)>,
+        root: &RpoDigest,
+    ) -> bool {
+        match self.compute_root(openings) {
+            Ok(computed_root) => root == &computed_root,
+            Err(_) => false,
+        }
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Inserts a node into the map, returning an error if a different value is already present at the
+/// same index.
+fn insert_checked(
+    nodes: &mut BTreeMap<NodeIndex, RpoDigest>,
+    index: NodeIndex,
+    value: RpoDigest,
+) -> Result<(), MerkleError> {
+    match nodes.insert(index, value) {
+        Some(existing) if existing != value => {
+            Err(MerkleError::ConflictingNodeValues { index, existing, provided: value })
+        }
+        _ => Ok(()),
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl Serializable for MultiProof {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_usize(self.nodes.len());
+        for (index, node) in self.nodes.iter() {
+            index.write_into(target);
+            node.write_into(target);
+        }
+    }
+}
+
+impl Deserializable for MultiProof {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_nodes = source.read_usize()?;
+        let mut nodes = Vec::with_capacity(num_nodes);
+        for _ in 0..num_nodes {
+            let index = NodeIndex::read_from(source)?;
+            let node = RpoDigest::read_from(source)?;
+            nodes.push((index, node));
+        }
+        Ok(Self::new(nodes))
+    }
+}
@@ -7,7 +7,7 @@ use core::fmt;
 
 use super::{
     InnerNodeInfo, MerkleError, MerklePath, NodeIndex, Rpo256, RpoDigest, ValuePath, Word,
-    EMPTY_WORD,
+    EMPTY_WORD, MAX_TREE_DEPTH,
 };
 use crate::utils::{
     word_to_hex, ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable,
@@ -54,7 +54,7 @@ impl PartialMerkleTree {
     pub const MIN_DEPTH: u8 = 1;
 
     /// Maximum supported depth.
-    pub const MAX_DEPTH: u8 = 64;
+    pub const MAX_DEPTH: u8 = MAX_TREE_DEPTH;
 
     // CONSTRUCTORS
     // --------------------------------------------------------------------------------------------
@@ -290,14 +290,16 @@ impl PartialMerkleTree {
         Self::check_depth(index_value.depth())?;
         self.update_depth(index_value.depth());
 
-        // add provided node and its sibling to the leaves set
-        self.leaves.insert(index_value);
+        // add provided node and its sibling to the nodes map; as for the siblings below, each of
+        // them becomes a leaf only if it is a new node, since it may be an inner node of another
+        // path
+        if self.nodes.insert(index_value, value).is_none() {
+            self.leaves.insert(index_value);
+        }
         let sibling_node_index = index_value.sibling();
-        self.leaves.insert(sibling_node_index);
-
-        // add provided node and its sibling to the nodes map
-        self.nodes.insert(index_value, value);
-        self.nodes.insert(sibling_node_index, path[0]);
+        if self.nodes.insert(sibling_node_index, path[0]).is_none() {
+            self.leaves.insert(sibling_node_index);
+        }
 
         // traverse to the root, updating the nodes
         let mut index_value = index_value;
@@ -341,46 +343,72 @@ impl PartialMerkleTree {
         Ok(())
     }
 
+    /// Adds all the paths of `other` to this tree.
+    ///
+    /// The nodes shared by both trees are stored only once.
+    ///
+    /// # Errors
+    /// Returns an error if the trees have different roots, unless either of them is empty.
+    pub fn merge(&mut self, other: &PartialMerkleTree) -> Result<(), MerkleError> {
+        if other.root() == EMPTY_DIGEST {
+            return Ok(());
+        } else if self.root() != EMPTY_DIGEST && self.root() != other.root() {
+            return Err(MerkleError::ConflictingRoots([self.root(), other.root()].to_vec()));
+        }
+
+        for (index, ValuePath { value, path }) in other.to_paths() {
+            self.add_path(index.value(), value, path)?;
+        }
+
+        Ok(())
+    }
+
     /// Updates value of the leaf at the specified index returning the old leaf value.
-    /// By default the specified index is assumed to belong to the deepest layer. If the considered
-    /// node does not belong to the tree, the first node on the way to the root will be changed.
     ///
     /// By default the specified index is assumed to belong to the deepest layer. If the considered
     /// node does not belong to the tree, the first node on the way to the root will be changed.
     ///
     /// This also recomputes all hashes between the leaf and the root, updating the root itself.
+    /// Only the nodes tracked by this tree are used, so the tree is left unchanged if any of them
+    /// is missing.
     ///
     /// # Errors
     /// Returns an error if:
     /// - The specified index is greater than the maximum number of nodes on the deepest layer.
+    /// - No leaf of this tree is on the way from the specified index to the root.
+    /// - A sibling of a node on the way from the leaf to the root is not tracked by this tree.
     pub fn update_leaf(&mut self, index: u64, value: Word) -> Result<RpoDigest, MerkleError> {
         let mut node_index = NodeIndex::new(self.max_depth(), index)?;
 
         // proceed to the leaf
-        for _ in 0..node_index.depth() {
-            if !self.leaves.contains(&node_index) {
-                node_index.move_up();
+        while !self.leaves.contains(&node_index) {
+            if node_index.is_root() {
+                return Err(MerkleError::NodeNotInSet(NodeIndex::new(self.max_depth(), index)?));
             }
+            node_index.move_up();
         }
 
-        // add node value to the nodes Map
-        let old_value = self
-            .nodes
-            .insert(node_index, value.into())
-            .ok_or(MerkleError::NodeNotInSet(node_index))?;
+        let old_value =
+            *self.nodes.get(&node_index).ok_or(MerkleError::NodeNotInSet(node_index))?;
 
         // if the old value and new value are the same, there is nothing to update
-        if value == *old_value {
+        let value: RpoDigest = value.into();
+        if value == old_value {
             return Ok(old_value);
         }
 
-        let mut value = value.into();
+        // compute the updated nodes up to the root before applying any of them
+        let mut updates = Vec::with_capacity(node_index.depth() as usize + 1);
+        updates.push((node_index, value));
+        let mut value = value;
         for _ in 0..node_index.depth() {
-            let sibling = self.nodes.get(&node_index.sibling()).expect("sibling should exist");
+            let sibling = node_index.sibling();
+            let sibling = self.nodes.get(&sibling).ok_or(MerkleError::NodeNotInSet(sibling))?;
             value = Rpo256::merge(&node_index.build_node(value, *sibling));
             node_index.move_up();
-            self.nodes.insert(node_index, value);
+            updates.push((node_index, value));
         }
+        self.nodes.extend(updates);
 
         Ok(old_value)
     }
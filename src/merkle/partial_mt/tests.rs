@@ -129,6 +129,85 @@ fn add_and_get_paths() {
     assert_eq!(expected_root, actual_root);
 }
 
+/// Checks that adding the path of a node which is an inner node of another path does not turn it
+/// into a leaf, regardless of the order in which the paths are added.
+#[test]
+fn add_path_of_inner_node() {
+    let mt = MerkleTree::new(digests_to_words(&VALUES8)).unwrap();
+    let ms = MerkleStore::from(&mt);
+
+    let path32 = ms.get_path(mt.root(), NODE32).unwrap();
+    let path21 = ms.get_path(mt.root(), NODE21).unwrap();
+
+    let mut inner_first = PartialMerkleTree::new();
+    inner_first.add_path(2, path32.value, path32.path.clone()).unwrap();
+    inner_first.add_path(1, path21.value, path21.path.clone()).unwrap();
+
+    let mut inner_last = PartialMerkleTree::new();
+    inner_last.add_path(1, path21.value, path21.path).unwrap();
+    inner_last.add_path(2, path32.value, path32.path).unwrap();
+
+    for pmt in [&inner_first, &inner_last] {
+        assert!(!pmt.is_leaf(NODE21));
+        assert!(pmt.is_leaf(NODE32));
+        assert!(pmt.is_leaf(NODE33));
+        assert!(pmt.is_leaf(NODE20));
+        assert_eq!(pmt.root(), mt.root());
+    }
+    assert_eq!(inner_first, inner_last);
+}
+
+/// Checks that merging PMTs with overlapping paths results in the PMT with all the paths, and that
+/// the nodes of the inner paths are not turned into leaves.
+#[test]
+fn merge() {
+    let mt = MerkleTree::new(digests_to_words(&VALUES8)).unwrap();
+    let expected_root = mt.root();
+
+    let ms = MerkleStore::from(&mt);
+
+    let path33 = ms.get_path(expected_root, NODE33).unwrap();
+    let path32 = ms.get_path(expected_root, NODE32).unwrap();
+    let path22 = ms.get_path(expected_root, NODE22).unwrap();
+
+    let mut pmt = PartialMerkleTree::with_paths([(3, path33.value, path33.path.clone())]).unwrap();
+    let other = PartialMerkleTree::with_paths([
+        (3, path32.value, path32.path.clone()),
+        (2, path22.value, path22.path.clone()),
+    ])
+    .unwrap();
+    pmt.merge(&other).unwrap();
+
+    let expected = PartialMerkleTree::with_paths([
+        (3, path33.value, path33.path),
+        (3, path32.value, path32.path),
+        (2, path22.value, path22.path),
+    ])
+    .unwrap();
+    assert_eq!(pmt, expected);
+    assert_eq!(pmt.root(), expected_root);
+    assert!(!pmt.is_leaf(NODE21));
+
+    // merging with an empty PMT, or into one, does not change the result
+    pmt.merge(&PartialMerkleTree::new()).unwrap();
+    assert_eq!(pmt, expected);
+    let mut empty = PartialMerkleTree::new();
+    empty.merge(&expected).unwrap();
+    assert_eq!(empty.root(), expected_root);
+}
+
+/// Checks that merging PMTs with different roots causes an error.
+#[test]
+fn err_merge() {
+    let path33 = vec![int_to_node(1), int_to_node(2), int_to_node(3)].into();
+    let path22 = vec![int_to_node(4), int_to_node(5)].into();
+
+    let mut pmt = PartialMerkleTree::with_paths([(3, int_to_node(6), path33)]).unwrap();
+    let other = PartialMerkleTree::with_paths([(2, int_to_node(7), path22)]).unwrap();
+
+    assert!(pmt.merge(&other).is_err());
+}
+
 /// Checks that function `get_node` used on nodes 10 and 32 returns expected values.
 #[test]
 fn get_node() {
@@ -461,4 +540,9 @@ fn err_update_leaf() {
     let mut pmt = PartialMerkleTree::with_paths([(3, path33.value, path33.path)]).unwrap();
 
     assert!(pmt.update_leaf(8, *int_to_node(38)).is_err());
+
+    // an empty tree has no leaf to update, and is left unchanged
+    let mut pmt = PartialMerkleTree::new();
+    assert!(pmt.update_leaf(0, *int_to_node(38)).is_err());
+    assert_eq!(pmt, PartialMerkleTree::new());
 }
@@ -1,9 +1,11 @@
-use alloc::vec::Vec;
+use alloc::{collections::BTreeMap, vec::Vec};
 use core::ops::{Deref, DerefMut};
 
-use super::{InnerNodeInfo, MerkleError, NodeIndex, Rpo256, RpoDigest};
+use super::{
+    EmptySubtreeRoots, InnerNodeInfo, MerkleError, NodeIndex, Rpo256, RpoDigest, MAX_TREE_DEPTH,
+};
 use crate::{
-    utils::{ByteReader, Deserializable, DeserializationError, Serializable},
+    utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable},
     Word,
 };
 
@@ -79,6 +81,53 @@ impl MerklePath {
             value: node,
         })
     }
+
+    // COMPACT SERIALIZATION
+    // --------------------------------------------------------------------------------------------
+
+    /// Writes this path into `target` in a compact form, which omits the siblings which are roots
+    /// of empty subtrees.
+    ///
+    /// This is meant for openings of sparse trees, most of whose siblings are roots of empty
+    /// subtrees. The path is written as:
+    /// - the number of siblings, as a single byte;
+    /// - a bitmap of one bit per sibling, rounded up to whole bytes, with bit `i` set if the
+    ///   `i`-th sibling, starting from the leaf, is the root of an empty subtree of depth `i`;
+    /// - the siblings which are not roots of empty subtrees.
+    pub fn write_compact_into<W: ByteWriter>(&self, target: &mut W) {
+        let mut bitmap = vec![0_u8; self.nodes.len().div_ceil(8)];
+        for (i, sibling) in self.nodes.iter().enumerate() {
+            if *sibling == *EmptySubtreeRoots::entry(i as u8, 0) {
+                bitmap[i / 8] |= 1 << (i % 8);
+            }
+        }
+
+        target.write_u8(self.nodes.len() as u8);
+        target.write_bytes(&bitmap);
+        for (i, sibling) in self.nodes.iter().enumerate() {
+            if bitmap[i / 8] & (1 << (i % 8)) == 0 {
+                sibling.write_into(target);
+            }
+        }
+    }
+
+    /// Reads a path written by [MerklePath::write_compact_into] from `source`, restoring the
+    /// omitted siblings.
+    pub fn read_compact_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let count: usize = read_path_depth(source)?.into();
+        let bitmap = source.read_vec(count.div_ceil(8))?;
+
+        let mut nodes = Vec::with_capacity(count);
+        for i in 0..count {
+            if bitmap[i / 8] & (1 << (i % 8)) == 0 {
+                nodes.push(RpoDigest::read_from(source)?);
+            } else {
+                nodes.push(*EmptySubtreeRoots::entry(i as u8, 0));
+            }
+        }
+
+        Ok(Self { nodes })
+    }
 }
 
 // CONVERSIONS
@@ -202,6 +251,180 @@ pub struct RootPath {
     pub path: MerklePath,
 }
 
+/// A self-contained opening of a node of a Merkle tree, composed of the index and value of the
+/// node, and of its [MerklePath].
+///
+/// The depth of the index always matches the length of the path, so the opening can be verified
+/// against a root without any further context.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "MerkleOpeningParts"))]
+pub struct MerkleOpening {
+    index: NodeIndex,
+    value: RpoDigest,
+    path: MerklePath,
+}
+
+impl MerkleOpening {
+    /// Returns a new [MerkleOpening] of the node with the specified index and value.
+    ///
+    /// # Errors
+    /// Returns an error if the depth of `index` is not the length of `path`.
+    pub fn new(index: NodeIndex, value: RpoDigest, path: MerklePath) -> Result<Self, MerkleError> {
+        if index.depth() != path.depth() {
+            return Err(MerkleError::InvalidDepth {
+                expected: index.depth(),
+                provided: path.depth(),
+            });
+        }
+        Ok(Self { index, value, path })
+    }
+
+    /// Returns the index of the opened node.
+    pub fn index(&self) -> NodeIndex {
+        self.index
+    }
+
+    /// Returns the value of the opened node.
+    pub fn value(&self) -> RpoDigest {
+        self.value
+    }
+
+    /// Returns the path from the opened node to the root (exclusive).
+    pub fn path(&self) -> &MerklePath {
+        &self.path
+    }
+
+    /// Computes the root of the tree this opening belongs to.
+    pub fn compute_root(&self) -> RpoDigest {
+        let mut index = self.index;
+        self.path.iter().fold(self.value, |node, sibling| {
+            let input = index.build_node(node, *sibling);
+            index.move_up();
+            Rpo256::merge(&input)
+        })
+    }
+
+    /// Returns true if the opened node exists at its index in a Merkle tree with `root`.
+    pub fn verify(&self, root: &RpoDigest) -> bool {
+        self.compute_root() == *root
+    }
+
+    /// Consumes the opening, returning its index, value and path.
+    pub fn into_parts(self) -> (NodeIndex, RpoDigest, MerklePath) {
+        (self.index, self.value, self.path)
+    }
+}
+
+/// The serialized fields of a [MerkleOpening], which are validated by [MerkleOpening::new] on
+/// deserialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct MerkleOpeningParts {
+    index: NodeIndex,
+    value: RpoDigest,
+    path: MerklePath,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<MerkleOpeningParts> for MerkleOpening {
+    type Error = MerkleError;
+
+    fn try_from(parts: MerkleOpeningParts) -> Result<Self, Self::Error> {
+        let index = NodeIndex::new(parts.index.depth(), parts.index.value())?;
+        Self::new(index, parts.value, parts.path)
+    }
+}
+
+impl TryFrom<(NodeIndex, ValuePath)> for MerkleOpening {
+    type Error = MerkleError;
+
+    fn try_from(
+        (index, ValuePath { value, path }): (NodeIndex, ValuePath),
+    ) -> Result<Self, Self::Error> {
+        Self::new(index, value, path)
+    }
+}
+
+impl TryFrom<(NodeIndex, RpoDigest, RootPath)> for MerkleOpening {
+    type Error = MerkleError;
+
+    /// The root of the [RootPath] is discarded, since it is computed from the opening itself.
+    fn try_from(
+        (index, value, root_path): (NodeIndex, RpoDigest, RootPath),
+    ) -> Result<Self, Self::Error> {
+        Self::new(index, value, root_path.path)
+    }
+}
+
+impl From<MerkleOpening> for ValuePath {
+    fn from(opening: MerkleOpening) -> Self {
+        ValuePath::new(opening.value, opening.path)
+    }
+}
+
+// BATCH VERIFICATION
+// ================================================================================================
+
+/// Returns true if every opening in `items` proves that its value is the node at the associated
+/// index of a Merkle tree with the specified `root`.
+///
+/// The nodes computed while verifying an opening are retained, so the verification of every
+/// subsequent opening stops at the first node it shares with a previously verified opening,
+/// instead of hashing all the way up to the root.
+///
+/// Openings whose path length does not match the depth of their index are rejected.
+pub fn verify_paths(root: &RpoDigest, items: &[(NodeIndex, ValuePath)]) -> bool {
+    let mut verified = VerifiedNodes::new(*root);
+    items
+        .iter()
+        .all(|(index, ValuePath { value, path })| verified.verify(*index, *value, path))
+}
+
+/// The nodes of a Merkle tree which have been verified against its root, as described in
+/// [verify_paths].
+pub(crate) struct VerifiedNodes {
+    nodes: BTreeMap<NodeIndex, RpoDigest>,
+}
+
+impl VerifiedNodes {
+    /// Returns a new set of verified nodes, holding only the root of the tree.
+    pub fn new(root: RpoDigest) -> Self {
+        Self {
+            nodes: BTreeMap::from([(NodeIndex::root(), root)]),
+        }
+    }
+
+    /// Returns true if `path` proves that `value` is the node at `index` of the tree, in which
+    /// case the nodes computed from the opening are retained.
+    pub fn verify(&mut self, index: NodeIndex, value: RpoDigest, path: &MerklePath) -> bool {
+        if index.depth() != path.depth() {
+            return false;
+        }
+
+        let mut index = index;
+        let mut node = value;
+        let mut siblings = path.iter();
+        let mut computed = Vec::new();
+        loop {
+            if let Some(known) = self.nodes.get(&index) {
+                // the rest of the path has already been verified up to the root
+                if *known != node {
+                    return false;
+                }
+                self.nodes.extend(computed);
+                return true;
+            }
+            computed.push((index, node));
+
+            // the index is not the root, since the root is always verified, so a sibling exists
+            let sibling = siblings.next().expect("path length matches the depth of the index");
+            node = Rpo256::merge(&index.build_node(node, *sibling));
+            index.move_up();
+        }
+    }
+}
+
 // SERIALIZATION
 // ================================================================================================
 
@@ -215,7 +438,7 @@ impl Serializable for MerklePath {
 
 impl Deserializable for MerklePath {
     fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
-        let count = source.read_u8()?.into();
+        let count = read_path_depth(source)?.into();
         let nodes = source.read_many::<RpoDigest>(count)?;
         Ok(Self { nodes })
     }
@@ -251,12 +474,99 @@ impl Deserializable for RootPath {
     }
 }
 
+impl Serializable for MerkleOpening {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        // the depth of the index is implied by the length of the path
+        target.write_u64(self.index.value());
+        self.value.write_into(target);
+        self.path.write_into(target);
+    }
+}
+
+impl Deserializable for MerkleOpening {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let value = source.read_u64()?;
+        let node = RpoDigest::read_from(source)?;
+        let path = MerklePath::read_from(source)?;
+        let index = NodeIndex::new(path.depth(), value)?;
+        Ok(Self { index, value: node, path })
+    }
+}
+
+// HELPER FUNCTIONS
+// ================================================================================================
+
+/// Reads the number of nodes of a serialized [MerklePath] from `source`.
+///
+/// Paths longer than [MAX_TREE_DEPTH] are rejected, since they can't be verified against any root.
+fn read_path_depth<R: ByteReader>(source: &mut R) -> Result<u8, DeserializationError> {
+    let depth = source.read_u8()?;
+    if depth > MAX_TREE_DEPTH {
+        return Err(DeserializationError::InvalidValue(format!(
+            "path depth {depth} exceeds the maximum tree depth {MAX_TREE_DEPTH}"
+        )));
+    }
+    Ok(depth)
+}
+
 // TESTS
 // ================================================================================================
 
 #[cfg(test)]
 mod tests {
-    use crate::merkle::{int_to_node, MerklePath};
+    use super::{verify_paths, MerkleOpening, RootPath, ValuePath};
+    use crate::{
+        merkle::{
+            int_to_leaf, int_to_node, LeafIndex, MerkleError, MerklePath, MerkleTree, NodeIndex,
+            SimpleSmt,
+        },
+        utils::{Deserializable, Serializable, SliceReader},
+    };
+    use alloc::vec::Vec;
+
+    #[test]
+    fn test_compact_serialization() {
+        // an opening of a sparse tree, whose siblings are mostly roots of empty subtrees
+        let mut smt = SimpleSmt::<32>::new().unwrap();
+        smt.insert(LeafIndex::new(3).unwrap(), int_to_leaf(1));
+        smt.insert(LeafIndex::new(1 << 20).unwrap(), int_to_leaf(2));
+        let path = smt.open(&LeafIndex::new(3).unwrap()).path;
+
+        let mut bytes = Vec::new();
+        path.write_compact_into(&mut bytes);
+        assert_eq!(bytes.len(), 1 + 4 + 32);
+        assert!(bytes.len() < path.to_bytes().len() / 2);
+        assert_eq!(MerklePath::read_compact_from(&mut SliceReader::new(&bytes)).unwrap(), path);
+
+        // paths without empty siblings round-trip as well
+        let path = MerklePath::new(vec![int_to_node(1), int_to_node(2), int_to_node(3)]);
+        let mut bytes = Vec::new();
+        path.write_compact_into(&mut bytes);
+        assert_eq!(MerklePath::read_compact_from(&mut SliceReader::new(&bytes)).unwrap(), path);
+    }
+
+    #[test]
+    fn test_path_serialization() {
+        let path = MerklePath::new(vec![int_to_node(1), int_to_node(2), int_to_node(3)]);
+        assert_eq!(MerklePath::read_from_bytes(&path.to_bytes()).unwrap(), path);
+
+        let value_path = ValuePath::new(int_to_node(4), path.clone());
+        assert_eq!(ValuePath::read_from_bytes(&value_path.to_bytes()).unwrap(), value_path);
+
+        let root_path = RootPath { root: int_to_node(5), path };
+        assert_eq!(RootPath::read_from_bytes(&root_path.to_bytes()).unwrap(), root_path);
+
+        // paths deeper than the maximum tree depth are rejected, in both encodings
+        let path: MerklePath = (0..65).map(int_to_node).collect();
+        assert!(MerklePath::read_from_bytes(&path.to_bytes()).is_err());
+        let mut bytes = Vec::new();
+        path.write_compact_into(&mut bytes);
+        assert!(MerklePath::read_compact_from(&mut SliceReader::new(&bytes)).is_err());
+
+        // truncated paths are rejected
+        let bytes = value_path.to_bytes();
+        assert!(ValuePath::read_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    }
 
     #[test]
     fn test_inner_nodes() {
@@ -271,4 +581,98 @@ mod tests {
 
         assert_eq!(root, inner_root);
     }
+
+    #[test]
+    fn test_merkle_opening() {
+        let leaves = [int_to_leaf(1), int_to_leaf(2), int_to_leaf(3), int_to_leaf(4)];
+        let tree = MerkleTree::new(leaves).unwrap();
+        let index = NodeIndex::new(2, 2).unwrap();
+
+        let value_path =
+            ValuePath::new(tree.get_node(index).unwrap(), tree.get_path(index).unwrap());
+        let opening = MerkleOpening::try_from((index, value_path.clone())).unwrap();
+        assert!(opening.verify(&tree.root()));
+        assert!(!opening.verify(&int_to_node(5)));
+        assert_eq!(opening.value(), leaves[2].into());
+
+        // the opening round-trips through its serialization, together with its position
+        let bytes = opening.to_bytes();
+        assert_eq!(MerkleOpening::read_from_bytes(&bytes).unwrap(), opening);
+
+        // conversions from the other path containers
+        assert_eq!(ValuePath::from(opening.clone()), value_path);
+        let root_path = RootPath {
+            root: tree.root(),
+            path: opening.path().clone(),
+        };
+        assert_eq!(MerkleOpening::try_from((index, opening.value(), root_path)).unwrap(), opening);
+
+        // the opening of a node at another position does not verify
+        let (_, value, path) = opening.into_parts();
+        let moved = MerkleOpening::new(NodeIndex::new(2, 1).unwrap(), value, path.clone()).unwrap();
+        assert!(!moved.verify(&tree.root()));
+
+        // the depth of the index must match the length of the path
+        assert_eq!(
+            MerkleOpening::new(NodeIndex::new(3, 2).unwrap(), value, path),
+            Err(MerkleError::InvalidDepth { expected: 3, provided: 2 })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_merkle_opening_deserialization_is_validated() {
+        use super::MerkleOpeningParts;
+
+        let path = MerklePath::new(vec![int_to_node(1), int_to_node(2)]);
+        let parts = |index| MerkleOpeningParts {
+            index,
+            value: int_to_node(3),
+            path: path.clone(),
+        };
+
+        let index = NodeIndex::new(2, 3).unwrap();
+        let opening = MerkleOpening::try_from(parts(index)).unwrap();
+        assert_eq!(opening, MerkleOpening::new(index, int_to_node(3), path.clone()).unwrap());
+
+        // indices which do not match the length of the path are rejected
+        let index = NodeIndex::new(3, 3).unwrap();
+        assert_eq!(
+            MerkleOpening::try_from(parts(index)),
+            Err(MerkleError::InvalidDepth { expected: 3, provided: 2 })
+        );
+    }
+
+    #[test]
+    fn test_verify_paths() {
+        let leaves: Vec<_> = (0..8).map(int_to_leaf).collect();
+        let tree = MerkleTree::new(&leaves).unwrap();
+        let root = tree.root();
+        let open = |depth, value| {
+            let index = NodeIndex::new(depth, value).unwrap();
+            (
+                index,
+                ValuePath::new(tree.get_node(index).unwrap(), tree.get_path(index).unwrap()),
+            )
+        };
+
+        // openings sharing nodes, at different depths, and repeated openings all verify
+        let items = [open(3, 0), open(3, 1), open(3, 6), open(2, 1), open(1, 1), open(3, 0)];
+        assert!(verify_paths(&root, &items));
+        assert!(verify_paths(&root, &[]));
+
+        // a single wrong value is detected, even if its path is shared with valid openings
+        let mut wrong = items.clone();
+        wrong[1].1.value = int_to_node(100);
+        assert!(!verify_paths(&root, &wrong));
+        let mut wrong = items.clone();
+        wrong[5].1.value = int_to_node(100);
+        assert!(!verify_paths(&root, &wrong));
+
+        // openings against another root, or whose index does not match the path, are rejected
+        assert!(!verify_paths(&int_to_node(100), &items));
+        let mut wrong = items;
+        wrong[2].0 = NodeIndex::new(2, 3).unwrap();
+        assert!(!verify_paths(&root, &wrong));
+    }
 }
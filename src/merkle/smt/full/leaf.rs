@@ -134,6 +134,12 @@ impl SmtLeaf {
     }
 
     /// Computes the hash of the leaf
+    ///
+    /// The entries of a leaf with multiple entries are hashed without a length prefix. Each entry
+    /// is 8 elements long, i.e., exactly the rate of RPO, so these inputs are never padded and
+    /// their number of entries is bound by the number of permutations. Since such leaves hold at
+    /// least 2 entries, their hash also takes more permutations than the merge computed for a
+    /// single entry.
     pub fn hash(&self) -> RpoDigest {
         match self {
             SmtLeaf::Empty(_) => EMPTY_WORD.into(),
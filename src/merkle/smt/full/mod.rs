@@ -1,6 +1,6 @@
 use super::{
-    EmptySubtreeRoots, Felt, InnerNode, InnerNodeInfo, LeafIndex, MerkleError, MerklePath,
-    NodeIndex, Rpo256, RpoDigest, SparseMerkleTree, Word, EMPTY_WORD,
+    build_inner_nodes, EmptySubtreeRoots, Felt, InnerNode, InnerNodeInfo, LeafIndex, MerkleError,
+    MerklePath, NodeIndex, Rpo256, RpoDigest, SparseMerkleTree, Word, EMPTY_WORD, SMT_MAX_DEPTH,
 };
 use alloc::{
     collections::{BTreeMap, BTreeSet},
@@ -14,8 +14,14 @@ pub use error::{SmtLeafError, SmtProofError};
 mod leaf;
 pub use leaf::SmtLeaf;
 
+mod mutations;
+pub use mutations::SmtMutationSet;
+
 mod proof;
 pub use proof::SmtProof;
+
+mod store_view;
+pub use store_view::SmtStoreView;
 use winter_utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
 
 #[cfg(test)]
@@ -24,7 +30,8 @@ mod tests;
 // CONSTANTS
 // ================================================================================================
 
-pub const SMT_DEPTH: u8 = 64;
+/// Depth of the [Smt]; leaves are addressed by a full 64-bit index.
+pub const SMT_DEPTH: u8 = SMT_MAX_DEPTH;
 
 // SMT
 // ================================================================================================
@@ -81,22 +88,24 @@ impl Smt {
         let mut tree = Self::new();
 
         // This being a sparse data structure, the EMPTY_WORD is not assigned to the `BTreeMap`, so
-        // entries with the empty value need additional tracking.
-        let mut key_set_to_zero = BTreeSet::new();
+        // all keys are tracked separately to detect duplicates.
+        let mut seen_keys = BTreeSet::new();
 
+        // populate the leaves first; the inner nodes are then computed once, bottom-up, rather than
+        // being recomputed on every insertion
         for (key, value) in entries {
-            let old_value = tree.insert(key, value);
-
-            if old_value != EMPTY_WORD || key_set_to_zero.contains(&key) {
+            if !seen_keys.insert(key) {
                 return Err(MerkleError::DuplicateValuesForIndex(
                     LeafIndex::<SMT_DEPTH>::from(key).value(),
                 ));
             }
 
-            if value == EMPTY_WORD {
-                key_set_to_zero.insert(key);
-            };
+            if value != EMPTY_WORD {
+                tree.perform_insert(key, value);
+            }
         }
+
+        tree.build_inner_nodes();
         Ok(tree)
     }
 
@@ -171,6 +180,65 @@ impl Smt {
         <Self as SparseMerkleTree<SMT_DEPTH>>::insert(self, key, value)
     }
 
+    /// Computes the changes resulting from setting the specified keys to the specified values,
+    /// without modifying this tree.
+    ///
+    /// The returned [SmtMutationSet] exposes the root the tree would have after the updates, and
+    /// can be committed with [Smt::apply_mutations]. Setting a key to [Self::EMPTY_VALUE] removes
+    /// it. If the same key appears more than once, the last value is used.
+    pub fn compute_mutations(
+        &self,
+        kv_pairs: impl IntoIterator<Item = (RpoDigest, Word)>,
+    ) -> SmtMutationSet {
+        let new_pairs: BTreeMap<RpoDigest, Word> = kv_pairs.into_iter().collect();
+
+        // compute the new state of each affected leaf
+        let mut new_leaves: BTreeMap<u64, SmtLeaf> = BTreeMap::new();
+        for (key, value) in new_pairs.iter() {
+            let leaf_index = LeafIndex::<SMT_DEPTH>::from(*key).value();
+            let leaf = new_leaves.entry(leaf_index).or_insert_with(|| self.get_leaf(key));
+            if *value == Self::EMPTY_VALUE {
+                leaf.remove(*key);
+            } else {
+                leaf.insert(*key, *value);
+            }
+        }
+
+        let leaf_hashes = new_leaves.iter().map(|(&index, leaf)| (index, leaf.hash())).collect();
+        let (node_mutations, new_root) = self.compute_node_mutations(leaf_hashes);
+
+        SmtMutationSet {
+            old_root: self.root,
+            node_mutations,
+            new_pairs,
+            new_root,
+        }
+    }
+
+    /// Applies the changes computed by [Smt::compute_mutations] to this tree.
+    ///
+    /// # Errors
+    /// Returns an error if the mutations were computed against a tree with a different root, in
+    /// which case the tree is left unchanged.
+    pub fn apply_mutations(&mut self, mutations: SmtMutationSet) -> Result<(), MerkleError> {
+        let SmtMutationSet {
+            old_root,
+            node_mutations,
+            new_pairs,
+            new_root,
+        } = mutations;
+        if old_root != self.root {
+            return Err(MerkleError::StaleMutationSet { expected: old_root, actual: self.root });
+        }
+
+        for (key, value) in new_pairs {
+            self.insert_value(key, value);
+        }
+        self.apply_node_mutations(node_mutations, new_root);
+
+        Ok(())
+    }
+
     // HELPERS
     // --------------------------------------------------------------------------------------------
 
@@ -191,6 +259,14 @@ impl Smt {
         }
     }
 
+    /// Computes all inner nodes and the root of this tree from its leaves.
+    ///
+    /// Must only be called on a tree with no inner nodes.
+    fn build_inner_nodes(&mut self) {
+        let leaves = self.leaves.iter().map(|(&key, leaf)| (key, leaf.hash())).collect();
+        self.root = build_inner_nodes::<SMT_DEPTH>(leaves, &mut self.inner_nodes);
+    }
+
     /// Removes key-value pair at leaf index pointed to by `key` if it exists.
     fn perform_remove(&mut self, key: RpoDigest) -> Option<Word> {
         let leaf_index: LeafIndex<SMT_DEPTH> = Self::key_to_leaf_index(&key);
@@ -0,0 +1,46 @@
+use super::{InnerNode, NodeIndex, RpoDigest, Word};
+use alloc::collections::BTreeMap;
+
+/// A set of changes to an [`super::Smt`], computed by [`super::Smt::compute_mutations`] and
+/// applied by [`super::Smt::apply_mutations`].
+///
+/// The mutation set records the root of the tree it was computed against, so that it is only
+/// ever applied to the same state of the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmtMutationSet {
+    /// The root of the tree the mutations were computed against.
+    pub(super) old_root: RpoDigest,
+
+    /// The inner nodes to update, with `None` for the nodes which become roots of empty subtrees
+    /// and must therefore be removed.
+    pub(super) node_mutations: BTreeMap<NodeIndex, Option<InnerNode>>,
+
+    /// The new values of the updated keys, with [`super::EMPTY_WORD`] for the removed keys.
+    pub(super) new_pairs: BTreeMap<RpoDigest, Word>,
+
+    /// The root of the tree once the mutations are applied.
+    pub(super) new_root: RpoDigest,
+}
+
+impl SmtMutationSet {
+    /// Returns the root of the tree against which these mutations were computed.
+    pub fn old_root(&self) -> RpoDigest {
+        self.old_root
+    }
+
+    /// Returns the root of the tree once these mutations are applied.
+    pub fn root(&self) -> RpoDigest {
+        self.new_root
+    }
+
+    /// Returns an iterator over the keys updated by these mutations, together with their new
+    /// values.
+    pub fn entries(&self) -> impl Iterator<Item = (&RpoDigest, &Word)> {
+        self.new_pairs.iter()
+    }
+
+    /// Returns true if these mutations do not update any key.
+    pub fn is_empty(&self) -> bool {
+        self.new_pairs.is_empty()
+    }
+}
@@ -1,6 +1,17 @@
-use super::{MerklePath, RpoDigest, SmtLeaf, SmtProofError, Word, SMT_DEPTH};
+use super::{
+    EmptySubtreeRoots, LeafIndex, MerklePath, RpoDigest, SmtLeaf, SmtProofError, Word, SMT_DEPTH,
+};
 use crate::utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
-use alloc::string::ToString;
+use alloc::{string::ToString, vec::Vec};
+
+// CONSTANTS
+// ================================================================================================
+
+/// Version of the serialization format of [SmtProof].
+const SMT_PROOF_SERIALIZATION_VERSION: u8 = 1;
+
+// SMT PROOF
+// ================================================================================================
 
 /// A proof which can be used to assert membership (or non-membership) of key-value pairs in a
 /// [`super::Smt`].
@@ -97,18 +108,87 @@ impl SmtProof {
     }
 }
 
+// SERIALIZATION
+// ================================================================================================
+
+/// Proofs are serialized compactly, as most of the siblings in a path of a sparse tree are roots
+/// of empty subtrees:
+/// - the version of the serialization format;
+/// - a 64-bit mask with bit `i` set if the `i`-th sibling of the path, starting from the leaf, is
+///   the root of an empty subtree;
+/// - the siblings which are not roots of empty subtrees;
+/// - the number of entries in the leaf, followed by the index of the leaf if it is empty, or by
+///   its entries otherwise, since the index of a non-empty leaf is derived from its keys.
 impl Serializable for SmtProof {
     fn write_into<W: ByteWriter>(&self, target: &mut W) {
-        self.path.write_into(target);
-        self.leaf.write_into(target);
+        target.write_u8(SMT_PROOF_SERIALIZATION_VERSION);
+
+        // Write: empty siblings mask
+        let mut empty_mask = 0_u64;
+        for (i, sibling) in self.path.iter().enumerate() {
+            if *sibling == *EmptySubtreeRoots::entry(SMT_DEPTH, SMT_DEPTH - i as u8) {
+                empty_mask |= 1 << i;
+            }
+        }
+        target.write_u64(empty_mask);
+
+        // Write: non-empty siblings
+        for (i, sibling) in self.path.iter().enumerate() {
+            if empty_mask & (1 << i) == 0 {
+                sibling.write_into(target);
+            }
+        }
+
+        // Write: leaf
+        let entries = self.leaf.entries();
+        target.write_usize(entries.len());
+        if entries.is_empty() {
+            target.write_u64(self.leaf.index().value());
+        }
+        for (key, value) in entries {
+            key.write_into(target);
+            value.write_into(target);
+        }
     }
 }
 
 impl Deserializable for SmtProof {
     fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
-        let path = MerklePath::read_from(source)?;
-        let leaf = SmtLeaf::read_from(source)?;
+        let version = source.read_u8()?;
+        if version != SMT_PROOF_SERIALIZATION_VERSION {
+            return Err(DeserializationError::InvalidValue(format!(
+                "unsupported SmtProof serialization version {version}"
+            )));
+        }
+
+        // Read: path
+        let empty_mask = source.read_u64()?;
+        let mut path = Vec::with_capacity(SMT_DEPTH.into());
+        for i in 0..SMT_DEPTH {
+            if empty_mask & (1 << i) == 0 {
+                path.push(source.read()?);
+            } else {
+                path.push(*EmptySubtreeRoots::entry(SMT_DEPTH, SMT_DEPTH - i));
+            }
+        }
+
+        // Read: leaf
+        let num_entries = source.read_usize()?;
+        let leaf = if num_entries == 0 {
+            SmtLeaf::new_empty(LeafIndex::new_max_depth(source.read_u64()?))
+        } else {
+            let mut entries: Vec<(RpoDigest, Word)> = Vec::new();
+            for _ in 0..num_entries {
+                let key: RpoDigest = source.read()?;
+                let value: Word = source.read()?;
+                entries.push((key, value));
+            }
+
+            let leaf_index = LeafIndex::from(entries[0].0);
+            SmtLeaf::new(entries, leaf_index)
+                .map_err(|err| DeserializationError::InvalidValue(err.to_string()))?
+        };
 
-        Self::new(path, leaf).map_err(|err| DeserializationError::InvalidValue(err.to_string()))
+        Ok(Self::new_unchecked(MerklePath::new(path), leaf))
     }
 }
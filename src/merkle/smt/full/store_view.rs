@@ -0,0 +1,102 @@
+use super::{
+    LeafIndex, MerkleError, NodeIndex, RpoDigest, SmtLeaf, SmtProof, Word, EMPTY_WORD, SMT_DEPTH,
+};
+use crate::{
+    merkle::{MerkleStore, StoreNode, ValuePath},
+    utils::collections::KvMap,
+};
+use alloc::collections::BTreeMap;
+
+// SMT STORE VIEW
+// ================================================================================================
+
+/// A read-only view of an [`super::Smt`] whose inner nodes are held in a [MerkleStore].
+///
+/// The store only holds the hashes of the leaves of the tree, so the view additionally keeps the
+/// pre-images of the non-empty leaves, indexed by their hash. This allows services which already
+/// maintain a [MerkleStore] to read values from and open an [`super::Smt`] without duplicating its
+/// nodes into a dedicated instance.
+#[derive(Debug, Clone)]
+pub struct SmtStoreView<'a, T: KvMap<RpoDigest, StoreNode>> {
+    store: &'a MerkleStore<T>,
+    root: RpoDigest,
+    leaves: BTreeMap<RpoDigest, SmtLeaf>,
+}
+
+impl<'a, T: KvMap<RpoDigest, StoreNode>> SmtStoreView<'a, T> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [SmtStoreView] of the tree with the specified root, whose inner nodes are held
+    /// in `store` and whose non-empty leaves are provided by `leaves`.
+    ///
+    /// Leaves which are not part of the tree are ignored.
+    pub fn new(
+        store: &'a MerkleStore<T>,
+        root: RpoDigest,
+        leaves: impl IntoIterator<Item = SmtLeaf>,
+    ) -> Self {
+        let leaves = leaves.into_iter().map(|leaf| (leaf.hash(), leaf)).collect();
+        Self { store, root, leaves }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the root of the tree.
+    pub fn root(&self) -> RpoDigest {
+        self.root
+    }
+
+    /// Returns the leaf to which `key` maps.
+    ///
+    /// # Errors
+    /// Returns an error if the path to the leaf is not in the store, or if the pre-image of a
+    /// non-empty leaf was not provided.
+    pub fn get_leaf(&self, key: &RpoDigest) -> Result<SmtLeaf, MerkleError> {
+        self.open_leaf(key).map(|(_, leaf)| leaf)
+    }
+
+    /// Returns the value associated with `key`.
+    ///
+    /// # Errors
+    /// Returns an error if the path to the leaf is not in the store, or if the pre-image of a
+    /// non-empty leaf was not provided.
+    pub fn get_value(&self, key: &RpoDigest) -> Result<Word, MerkleError> {
+        let leaf = self.get_leaf(key)?;
+        Ok(leaf.get_value(key).unwrap_or_default())
+    }
+
+    /// Returns an opening of the leaf associated with `key`.
+    ///
+    /// # Errors
+    /// Returns an error if the path to the leaf is not in the store, or if the pre-image of a
+    /// non-empty leaf was not provided, in which case [MerkleError::NodeNotInStore] is returned
+    /// with the hash and the index of the leaf.
+    pub fn open(&self, key: &RpoDigest) -> Result<SmtProof, MerkleError> {
+        let (path, leaf) = self.open_leaf(key)?;
+        Ok(SmtProof::new_unchecked(path.path, leaf))
+    }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the path to the leaf to which `key` maps, together with the pre-image of the leaf.
+    fn open_leaf(&self, key: &RpoDigest) -> Result<(ValuePath, SmtLeaf), MerkleError> {
+        let leaf_index = LeafIndex::<SMT_DEPTH>::from(*key);
+        let index = NodeIndex::from(leaf_index);
+        let path = self.store.get_path(self.root, index)?;
+
+        let leaf = if path.value == RpoDigest::from(EMPTY_WORD) {
+            SmtLeaf::new_empty(leaf_index)
+        } else {
+            match self.leaves.get(&path.value) {
+                Some(leaf) if leaf.index() == leaf_index => leaf.clone(),
+                // the pre-image of the leaf was not provided to the view
+                _ => return Err(MerkleError::NodeNotInStore(path.value, index)),
+            }
+        };
+
+        Ok((path, leaf))
+    }
+}
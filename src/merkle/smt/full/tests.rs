@@ -1,4 +1,7 @@
-use super::{Felt, LeafIndex, NodeIndex, Rpo256, RpoDigest, Smt, SmtLeaf, EMPTY_WORD, SMT_DEPTH};
+use super::{
+    Felt, LeafIndex, MerkleError, NodeIndex, Rpo256, RpoDigest, Smt, SmtLeaf, SmtProof,
+    SmtStoreView, EMPTY_WORD, SMT_DEPTH,
+};
 use crate::{
     merkle::{EmptySubtreeRoots, MerkleStore},
     utils::{Deserializable, Serializable},
@@ -257,6 +260,81 @@ fn test_smt_removal() {
     }
 }
 
+#[test]
+fn test_smt_compute_and_apply_mutations() {
+    let key_1 = RpoDigest::from([ONE, ONE, ONE, Felt::new(42)]);
+    let key_2 = RpoDigest::from([ONE + ONE, ONE, ONE, Felt::new(42)]);
+    let key_3 = RpoDigest::from([ONE, ONE, ONE, Felt::new(7)]);
+    let mut smt =
+        Smt::with_entries([(key_1, [ONE; WORD_SIZE]), (key_3, [ONE; WORD_SIZE])]).unwrap();
+    let smt_before_update = smt.clone();
+
+    // add a key to an existing leaf, remove a key, and update a key twice
+    let updates = [
+        (key_2, [ONE + ONE; WORD_SIZE]),
+        (key_3, EMPTY_WORD),
+        (key_1, [ONE + ONE; WORD_SIZE]),
+        (key_1, [ONE + ONE + ONE; WORD_SIZE]),
+    ];
+    let mut expected = smt.clone();
+    for (key, value) in updates {
+        expected.insert(key, value);
+    }
+
+    // computing the mutations predicts the new root without modifying the tree
+    let mutations = smt.compute_mutations(updates);
+    assert_eq!(mutations.old_root(), smt.root());
+    assert_eq!(mutations.root(), expected.root());
+    assert_eq!(mutations.entries().count(), 3);
+    assert_eq!(smt, smt_before_update);
+
+    smt.apply_mutations(mutations.clone()).unwrap();
+    assert_eq!(smt, expected);
+
+    // mutations computed against a different root are rejected
+    assert_eq!(
+        smt.apply_mutations(mutations),
+        Err(MerkleError::StaleMutationSet {
+            expected: smt_before_update.root(),
+            actual: expected.root()
+        })
+    );
+    assert_eq!(smt, expected);
+
+    // removing every key restores the empty tree
+    let mutations = smt.compute_mutations([key_1, key_2].map(|key| (key, EMPTY_WORD)));
+    assert_eq!(mutations.root(), *EmptySubtreeRoots::entry(SMT_DEPTH, 0));
+    smt.apply_mutations(mutations).unwrap();
+    assert_eq!(smt, Smt::new());
+}
+
+#[test]
+fn test_smt_store_view() {
+    let key_1 = RpoDigest::from([ONE, ONE, ONE, Felt::new(42)]);
+    let key_2 = RpoDigest::from([ONE + ONE, ONE, ONE, Felt::new(42)]);
+    let key_3 = RpoDigest::from([ONE, ONE, ONE, Felt::new(7)]);
+    let smt = Smt::with_entries([
+        (key_1, [ONE; WORD_SIZE]),
+        (key_2, [ONE + ONE; WORD_SIZE]),
+        (key_3, [ONE; WORD_SIZE]),
+    ])
+    .unwrap();
+
+    let store: MerkleStore = MerkleStore::from(&smt);
+    let view = SmtStoreView::new(&store, smt.root(), smt.leaves().map(|(_, leaf)| leaf.clone()));
+
+    let absent_key = RpoDigest::from([ONE, ONE, ONE, Felt::new(8)]);
+    for key in [key_1, key_2, key_3, absent_key] {
+        assert_eq!(view.get_value(&key).unwrap(), smt.get_value(&key));
+        assert_eq!(view.open(&key).unwrap(), smt.open(&key));
+    }
+
+    // without the pre-image of a non-empty leaf, the leaf cannot be opened
+    let view = SmtStoreView::new(&store, smt.root(), [smt.get_leaf(&key_3)]);
+    assert!(view.open(&key_3).is_ok());
+    assert!(view.get_value(&key_1).is_err());
+}
+
 /// Tests that 2 key-value pairs stored in the same leaf have the same path
 #[test]
 fn test_smt_path_to_keys_in_same_leaf_are_equal() {
@@ -274,6 +352,35 @@ fn test_smt_path_to_keys_in_same_leaf_are_equal() {
     assert_eq!(smt.open(&key_1), smt.open(&key_2));
 }
 
+/// This test checks that building a tree from its entries matches inserting them one by one,
+/// including for keys sharing a leaf and entries set to the empty word
+#[test]
+fn test_smt_with_entries_matches_inserts() {
+    let mut entries: Vec<(RpoDigest, Word)> = (0..500_u64)
+        .map(|i| {
+            let key = RpoDigest::from([Felt::new(i), ONE, ONE, Felt::new(i * 0x1234_5678_9abc)]);
+            (key, [Felt::new(i + 1); WORD_SIZE])
+        })
+        .collect();
+
+    // keys in the same leaf, and a key explicitly set to the empty word
+    let leaf_prefix = Felt::new(0x1234_5678_9abc);
+    entries.push((RpoDigest::from([Felt::new(1000), ONE, ONE, leaf_prefix]), [ONE; WORD_SIZE]));
+    entries.push((RpoDigest::from([Felt::new(1001), ONE, ONE, leaf_prefix]), EMPTY_WORD));
+
+    let smt = Smt::with_entries(entries.clone()).unwrap();
+
+    let mut expected = Smt::new();
+    for (key, value) in entries.iter() {
+        expected.insert(*key, *value);
+    }
+    assert_eq!(smt, expected);
+
+    // duplicate keys are rejected, even when set to the empty word
+    entries.push((RpoDigest::from([Felt::new(1001), ONE, ONE, leaf_prefix]), [ONE; WORD_SIZE]));
+    assert!(Smt::with_entries(entries).is_err());
+}
+
 /// Tests that an empty leaf hashes to the empty word
 #[test]
 fn test_empty_leaf_hash() {
@@ -332,6 +439,45 @@ fn test_smt_entries() {
 // SMT LEAF
 // --------------------------------------------------------------------------------------------
 
+#[test]
+fn test_smt_proof_serialization() {
+    let key_1 = RpoDigest::from([ONE, ONE, ONE, Felt::new(42)]);
+    let key_2 = RpoDigest::from([ONE + ONE, ONE, ONE, Felt::new(42)]);
+    let key_3 = RpoDigest::from([ONE, ONE, ONE, Felt::new(u64::MAX >> 1)]);
+    let smt = Smt::with_entries([
+        (key_1, [ONE; WORD_SIZE]),
+        (key_2, [ONE + ONE; WORD_SIZE]),
+        (key_3, [ONE; WORD_SIZE]),
+    ])
+    .unwrap();
+
+    // proofs of a multiple, a single and an empty leaf. The paths of leaves 42 and 43 diverge
+    // from the path of leaf 2^63 - 1 at depth 2, so the only siblings which are not roots of empty
+    // subtrees are at depth 2 and, for leaf 43, the leaf 42.
+    let empty_key = RpoDigest::from([ONE, ONE, ONE, Felt::new(43)]);
+    let expected_lengths = [
+        // version, mask, 1 sibling, number of entries, 2 entries
+        1 + 8 + 32 + 1 + 2 * 64,
+        // version, mask, 1 sibling, number of entries, 1 entry
+        1 + 8 + 32 + 1 + 64,
+        // version, mask, 2 siblings, number of entries, leaf index
+        1 + 8 + 2 * 32 + 1 + 8,
+    ];
+    for (key, expected_len) in [key_1, key_3, empty_key].into_iter().zip(expected_lengths) {
+        let proof = smt.open(&key);
+        let bytes = proof.to_bytes();
+        assert_eq!(bytes.len(), expected_len);
+        assert_eq!(SmtProof::read_from_bytes(&bytes).unwrap(), proof);
+        assert_eq!(proof.compute_root(), smt.root());
+    }
+
+    // unknown versions and truncated proofs are rejected
+    let mut bytes = smt.open(&key_1).to_bytes();
+    assert!(SmtProof::read_from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    bytes[0] += 1;
+    assert!(SmtProof::read_from_bytes(&bytes).is_err());
+}
+
 #[test]
 fn test_empty_smt_leaf_serialization() {
     let empty_leaf = SmtLeaf::new_empty(LeafIndex::new_max_depth(42));
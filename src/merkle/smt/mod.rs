@@ -1,15 +1,20 @@
-use super::{EmptySubtreeRoots, InnerNodeInfo, MerkleError, MerklePath, NodeIndex};
+use super::{EmptySubtreeRoots, InnerNodeInfo, MerkleError, MerklePath, NodeIndex, MAX_TREE_DEPTH};
 use crate::{
     hash::rpo::{Rpo256, RpoDigest},
     Felt, Word, EMPTY_WORD,
 };
-use alloc::vec::Vec;
+use alloc::{collections::BTreeMap, vec::Vec};
 
 mod full;
-pub use full::{Smt, SmtLeaf, SmtLeafError, SmtProof, SmtProofError, SMT_DEPTH};
+pub use full::{
+    Smt, SmtLeaf, SmtLeafError, SmtMutationSet, SmtProof, SmtProofError, SmtStoreView, SMT_DEPTH,
+};
 
 mod simple;
-pub use simple::SimpleSmt;
+pub use simple::{
+    DuplicatePolicy, SimpleSmt, SimpleSmtBuilder, SimpleSmtMutationSet,
+    SimpleSmtNonMembershipProof, SimpleSmtProof, TypedSmt,
+};
 
 // CONSTANTS
 // ================================================================================================
@@ -18,7 +23,12 @@ pub use simple::SimpleSmt;
 pub const SMT_MIN_DEPTH: u8 = 1;
 
 /// Maximum supported depth.
-pub const SMT_MAX_DEPTH: u8 = 64;
+pub const SMT_MAX_DEPTH: u8 = MAX_TREE_DEPTH;
+
+/// Depth at which the construction of a sparse Merkle tree is split into subtrees built in
+/// parallel.
+#[cfg(feature = "rayon")]
+const PARALLEL_SPLIT_DEPTH: u8 = 8;
 
 // SPARSE MERKLE TREE
 // ================================================================================================
@@ -139,6 +149,68 @@ pub(crate) trait SparseMerkleTree<const DEPTH: u8> {
         self.set_root(node_hash);
     }
 
+    /// Computes the inner nodes affected by setting the leaves at the specified positions to the
+    /// specified hashes, without modifying the tree.
+    ///
+    /// Each inner node is recomputed only once, regardless of the number of updated leaves below
+    /// it. Returns the new values of the affected inner nodes, with `None` for the nodes which
+    /// become roots of empty subtrees, together with the resulting root of the tree.
+    fn compute_node_mutations(
+        &self,
+        leaf_hashes: BTreeMap<u64, RpoDigest>,
+    ) -> (BTreeMap<NodeIndex, Option<InnerNode>>, RpoDigest) {
+        let mut dirty_nodes = leaf_hashes;
+        let mut node_mutations = BTreeMap::new();
+
+        // recompute the dirty nodes level by level, merging the updates which share a parent
+        for node_depth in (0..DEPTH).rev() {
+            let mut parents = BTreeMap::new();
+            for (&value, &node_hash) in dirty_nodes.iter() {
+                let parent_value = value >> 1;
+                if parents.contains_key(&parent_value) {
+                    continue;
+                }
+
+                let index = NodeIndex::new_unchecked(node_depth, parent_value);
+                let InnerNode { left, right } = self.get_inner_node(index);
+                let (left, right) = if value & 1 == 1 {
+                    (*dirty_nodes.get(&(value - 1)).unwrap_or(&left), node_hash)
+                } else {
+                    (node_hash, *dirty_nodes.get(&(value + 1)).unwrap_or(&right))
+                };
+
+                let inner_node = InnerNode { left, right };
+                let parent_hash = inner_node.hash();
+                if parent_hash == *EmptySubtreeRoots::entry(DEPTH, node_depth) {
+                    node_mutations.insert(index, None);
+                } else {
+                    node_mutations.insert(index, Some(inner_node));
+                }
+                parents.insert(parent_value, parent_hash);
+            }
+            dirty_nodes = parents;
+        }
+
+        let root = dirty_nodes.remove(&0).unwrap_or_else(|| self.root());
+        (node_mutations, root)
+    }
+
+    /// Applies inner node mutations computed by [Self::compute_node_mutations], and sets the root
+    /// of the tree to `root`.
+    fn apply_node_mutations(
+        &mut self,
+        node_mutations: BTreeMap<NodeIndex, Option<InnerNode>>,
+        root: RpoDigest,
+    ) {
+        for (index, inner_node) in node_mutations {
+            match inner_node {
+                Some(inner_node) => self.insert_inner_node(index, inner_node),
+                None => self.remove_inner_node(index),
+            }
+        }
+        self.set_root(root);
+    }
+
     // REQUIRED METHODS
     // ---------------------------------------------------------------------------------------------
 
@@ -191,6 +263,116 @@ impl InnerNode {
     }
 }
 
+// TREE CONSTRUCTION
+// ================================================================================================
+
+/// Computes the inner nodes and the root of a sparse Merkle tree of depth `DEPTH` from the hashes
+/// of its non-empty leaves, adding the inner nodes to `inner_nodes`.
+///
+/// The leaves must be sorted by their position. Returns the root of the tree.
+#[cfg(not(feature = "rayon"))]
+pub(crate) fn build_inner_nodes<const DEPTH: u8>(
+    leaves: Vec<(u64, RpoDigest)>,
+    inner_nodes: &mut BTreeMap<NodeIndex, InnerNode>,
+) -> RpoDigest {
+    match build_subtree::<DEPTH>(leaves, DEPTH, 0, inner_nodes).first() {
+        Some(&(_, root)) => root,
+        None => *EmptySubtreeRoots::entry(DEPTH, 0),
+    }
+}
+
+/// Computes the inner nodes and the root of a sparse Merkle tree of depth `DEPTH` from the hashes
+/// of its non-empty leaves, adding the inner nodes to `inner_nodes`.
+///
+/// The leaves are split into subtrees rooted at depth [PARALLEL_SPLIT_DEPTH], which are built in
+/// parallel; the nodes above the split depth are then computed from the subtree roots.
+///
+/// The leaves must be sorted by their position. Returns the root of the tree.
+#[cfg(feature = "rayon")]
+pub(crate) fn build_inner_nodes<const DEPTH: u8>(
+    leaves: Vec<(u64, RpoDigest)>,
+    inner_nodes: &mut BTreeMap<NodeIndex, InnerNode>,
+) -> RpoDigest {
+    use rayon::prelude::*;
+
+    let split_depth = PARALLEL_SPLIT_DEPTH.min(DEPTH);
+    let shift = DEPTH - split_depth;
+
+    // group the leaves by the subtree they belong to; since the leaves are sorted, so are the
+    // groups
+    let mut subtrees: Vec<Vec<(u64, RpoDigest)>> = Vec::new();
+    let mut current_subtree = None;
+    for (key, leaf_hash) in leaves {
+        if current_subtree != Some(key >> shift) {
+            current_subtree = Some(key >> shift);
+            subtrees.push(Vec::new());
+        }
+        subtrees.last_mut().expect("subtree was just added").push((key, leaf_hash));
+    }
+
+    let subtrees: Vec<_> = subtrees
+        .into_par_iter()
+        .map(|leaves| {
+            let mut subtree_nodes = BTreeMap::new();
+            let roots = build_subtree::<DEPTH>(leaves, DEPTH, split_depth, &mut subtree_nodes);
+            (roots, subtree_nodes)
+        })
+        .collect();
+
+    let mut subtree_roots = Vec::with_capacity(subtrees.len());
+    for (roots, mut subtree_nodes) in subtrees {
+        subtree_roots.extend(roots);
+        inner_nodes.append(&mut subtree_nodes);
+    }
+
+    match build_subtree::<DEPTH>(subtree_roots, split_depth, 0, inner_nodes).first() {
+        Some(&(_, root)) => root,
+        None => *EmptySubtreeRoots::entry(DEPTH, 0),
+    }
+}
+
+/// Computes the inner nodes between `depth` and `target_depth` from the provided nodes at
+/// `depth`, adding them to `inner_nodes`.
+///
+/// The provided nodes must be sorted by their position. Returns the nodes at `target_depth`
+/// which are not roots of empty subtrees, sorted by their position.
+fn build_subtree<const DEPTH: u8>(
+    mut nodes: Vec<(u64, RpoDigest)>,
+    mut depth: u8,
+    target_depth: u8,
+    inner_nodes: &mut BTreeMap<NodeIndex, InnerNode>,
+) -> Vec<(u64, RpoDigest)> {
+    while depth > target_depth {
+        let empty_node = *EmptySubtreeRoots::entry(DEPTH, depth);
+        let empty_parent = *EmptySubtreeRoots::entry(DEPTH, depth - 1);
+
+        let mut parents = Vec::with_capacity(nodes.len() / 2 + 1);
+        let mut nodes_iter = nodes.into_iter().peekable();
+        while let Some((value, node)) = nodes_iter.next() {
+            let (left, right) = if value & 1 == 1 {
+                (empty_node, node)
+            } else {
+                match nodes_iter.next_if(|&(sibling, _)| sibling == value + 1) {
+                    Some((_, sibling)) => (node, sibling),
+                    None => (node, empty_node),
+                }
+            };
+
+            let inner_node = InnerNode { left, right };
+            let parent = inner_node.hash();
+            if parent != empty_parent {
+                inner_nodes.insert(NodeIndex::new_unchecked(depth - 1, value >> 1), inner_node);
+                parents.push((value >> 1, parent));
+            }
+        }
+
+        nodes = parents;
+        depth -= 1;
+    }
+
+    nodes
+}
+
 // LEAF INDEX
 // ================================================================================================
 
@@ -0,0 +1,117 @@
+use super::{LeafIndex, MerkleError, SimpleSmt, Word};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+
+// DUPLICATE POLICY
+// ================================================================================================
+
+/// Specifies how a [SimpleSmtBuilder] handles entries which set the same leaf more than once.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// Building the tree fails with [MerkleError::DuplicateValuesForIndex] on the first repeated
+    /// index.
+    #[default]
+    Error,
+
+    /// The value of the last entry for a given index is kept.
+    LastWriteWins,
+}
+
+// SIMPLE SMT BUILDER
+// ================================================================================================
+
+/// A builder for constructing a [SimpleSmt] from a list of entries.
+///
+/// In addition to the tree itself, the builder reports the indices which appeared more than once
+/// in the entries, which allows bulk loaders to detect inconsistencies in their input.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct SimpleSmtBuilder<const DEPTH: u8> {
+    duplicate_policy: DuplicatePolicy,
+}
+
+impl<const DEPTH: u8> SimpleSmtBuilder<DEPTH> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [SimpleSmtBuilder] which rejects duplicate indices.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the policy applied to entries which set the same leaf more than once.
+    pub fn with_duplicate_policy(mut self, duplicate_policy: DuplicatePolicy) -> Self {
+        self.duplicate_policy = duplicate_policy;
+        self
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the policy applied to entries which set the same leaf more than once.
+    pub fn duplicate_policy(&self) -> DuplicatePolicy {
+        self.duplicate_policy
+    }
+
+    // BUILDER
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [SimpleSmt] instantiated with leaves set as specified by the provided entries,
+    /// together with the sorted list of indices which were set more than once.
+    ///
+    /// All leaves omitted from the entries list are set to [ZERO; 4].
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - If the depth is 0 or is greater than 64.
+    /// - The number of entries exceeds the maximum tree capacity, that is 2^{depth}, and the
+    ///   duplicate policy is [DuplicatePolicy::Error].
+    /// - The provided entries contain multiple values for the same key, and the duplicate policy
+    ///   is [DuplicatePolicy::Error].
+    pub fn build(
+        &self,
+        entries: impl IntoIterator<Item = (u64, Word)>,
+    ) -> Result<(SimpleSmt<DEPTH>, Vec<u64>), MerkleError> {
+        // create an empty tree
+        let mut tree = SimpleSmt::<DEPTH>::new()?;
+
+        // compute the max number of entries. We use an upper bound of depth 63 because we consider
+        // passing in a vector of size 2^64 infeasible.
+        let max_num_entries = 2_usize.pow(DEPTH.min(63).into());
+
+        // This being a sparse data structure, the EMPTY_WORD is not assigned to the `BTreeMap`, so
+        // entries with the empty value need additional tracking.
+        let mut key_set_to_zero = BTreeSet::new();
+        let mut duplicates = BTreeSet::new();
+
+        for (idx, (key, value)) in entries.into_iter().enumerate() {
+            if idx >= max_num_entries && self.duplicate_policy == DuplicatePolicy::Error {
+                return Err(MerkleError::InvalidNumEntries(max_num_entries));
+            }
+
+            let key = LeafIndex::<DEPTH>::new(key)?.value();
+            if tree.leaves.contains_key(&key) || key_set_to_zero.contains(&key) {
+                match self.duplicate_policy {
+                    DuplicatePolicy::Error => {
+                        return Err(MerkleError::DuplicateValuesForIndex(key));
+                    }
+                    DuplicatePolicy::LastWriteWins => {
+                        duplicates.insert(key);
+                    }
+                }
+            }
+
+            if value == SimpleSmt::<DEPTH>::EMPTY_VALUE {
+                tree.leaves.remove(&key);
+                key_set_to_zero.insert(key);
+            } else {
+                key_set_to_zero.remove(&key);
+                tree.leaves.insert(key, value);
+            }
+        }
+
+        tree.build_inner_nodes();
+        Ok((tree, duplicates.into_iter().collect()))
+    }
+}
@@ -1,9 +1,22 @@
 use super::{
-    super::ValuePath, EmptySubtreeRoots, InnerNode, InnerNodeInfo, LeafIndex, MerkleError,
-    MerklePath, NodeIndex, RpoDigest, SparseMerkleTree, Word, EMPTY_WORD, SMT_MAX_DEPTH,
-    SMT_MIN_DEPTH,
+    super::ValuePath, build_inner_nodes, EmptySubtreeRoots, InnerNode, InnerNodeInfo, LeafIndex,
+    MerkleError, MerklePath, NodeIndex, RpoDigest, SparseMerkleTree, Word, EMPTY_WORD,
+    SMT_MAX_DEPTH, SMT_MIN_DEPTH,
 };
-use alloc::collections::{BTreeMap, BTreeSet};
+use crate::utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+use alloc::{collections::BTreeMap, string::ToString, vec::Vec};
+
+mod builder;
+pub use builder::{DuplicatePolicy, SimpleSmtBuilder};
+
+mod mutations;
+pub use mutations::SimpleSmtMutationSet;
+
+mod proof;
+pub use proof::{SimpleSmtNonMembershipProof, SimpleSmtProof};
+
+mod typed;
+pub use typed::TypedSmt;
 
 #[cfg(test)]
 mod tests;
@@ -64,8 +77,27 @@ impl<const DEPTH: u8> SimpleSmt<DEPTH> {
     /// - If the depth is 0 or is greater than 64.
     /// - The number of entries exceeds the maximum tree capacity, that is 2^{depth}.
     /// - The provided entries contain multiple values for the same key.
+    ///
+    /// To tolerate duplicate keys, use a [SimpleSmtBuilder] with [DuplicatePolicy::LastWriteWins].
     pub fn with_leaves(
         entries: impl IntoIterator<Item = (u64, Word)>,
+    ) -> Result<Self, MerkleError> {
+        SimpleSmtBuilder::new().build(entries).map(|(tree, _)| tree)
+    }
+
+    /// Returns a new [SimpleSmt] with the provided values set at contiguous indices starting at
+    /// index 0.
+    ///
+    /// Only the inner nodes above the provided values are computed, while the remainder of the
+    /// tree is represented by the roots of empty subtrees. Thus, building a tree from `n` values
+    /// requires O(n + DEPTH) hash evaluations, regardless of the depth of the tree.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - If the depth is 0 or is greater than 64.
+    /// - The number of values exceeds the maximum tree capacity, that is 2^{depth}.
+    pub fn with_contiguous_leaves(
+        entries: impl IntoIterator<Item = Word>,
     ) -> Result<Self, MerkleError> {
         // create an empty tree
         let mut tree = Self::new()?;
@@ -74,39 +106,24 @@ impl<const DEPTH: u8> SimpleSmt<DEPTH> {
         // passing in a vector of size 2^64 infeasible.
         let max_num_entries = 2_usize.pow(DEPTH.min(63).into());
 
-        // This being a sparse data structure, the EMPTY_WORD is not assigned to the `BTreeMap`, so
-        // entries with the empty value need additional tracking.
-        let mut key_set_to_zero = BTreeSet::new();
-
-        for (idx, (key, value)) in entries.into_iter().enumerate() {
+        // indices are unique by construction, so only the number of entries needs validating
+        for (idx, value) in entries.into_iter().enumerate() {
             if idx >= max_num_entries {
                 return Err(MerkleError::InvalidNumEntries(max_num_entries));
             }
 
-            let old_value = tree.insert(LeafIndex::<DEPTH>::new(key)?, value);
-
-            if old_value != Self::EMPTY_VALUE || key_set_to_zero.contains(&key) {
-                return Err(MerkleError::DuplicateValuesForIndex(key));
+            if value != Self::EMPTY_VALUE {
+                tree.leaves.insert(idx as u64, value);
             }
-
-            if value == Self::EMPTY_VALUE {
-                key_set_to_zero.insert(key);
-            };
         }
+
+        tree.build_inner_nodes();
         Ok(tree)
     }
 
-    /// Wrapper around [`SimpleSmt::with_leaves`] which inserts leaves at contiguous indices
-    /// starting at index 0.
-    pub fn with_contiguous_leaves(
-        entries: impl IntoIterator<Item = Word>,
-    ) -> Result<Self, MerkleError> {
-        Self::with_leaves(
-            entries
-                .into_iter()
-                .enumerate()
-                .map(|(idx, word)| (idx.try_into().expect("tree max depth is 2^8"), word)),
-        )
+    /// Returns a new [SimpleSmtBuilder] for constructing a tree of this depth.
+    pub fn builder() -> SimpleSmtBuilder<DEPTH> {
+        SimpleSmtBuilder::new()
     }
 
     // PUBLIC ACCESSORS
@@ -157,14 +174,108 @@ impl<const DEPTH: u8> SimpleSmt<DEPTH> {
         <Self as SparseMerkleTree<DEPTH>>::open(self, key)
     }
 
+    /// Returns a self-contained proof of the value of the leaf associated with `key`, which can be
+    /// verified against the root of this tree.
+    pub fn prove(&self, key: &LeafIndex<DEPTH>) -> SimpleSmtProof<DEPTH> {
+        let ValuePath { value, path } = self.open(key);
+        SimpleSmtProof::new(*key, value.into(), path).expect("path length is equal to DEPTH")
+    }
+
+    /// Returns a proof that the leaf associated with `key` is empty, or `None` if the leaf holds a
+    /// value.
+    ///
+    /// The proof opens the shallowest subtree containing the leaf which holds only empty leaves,
+    /// and can be verified against the root of this tree using [EmptySubtreeRoots].
+    pub fn prove_non_membership(
+        &self,
+        key: &LeafIndex<DEPTH>,
+    ) -> Option<SimpleSmtNonMembershipProof<DEPTH>> {
+        if self.get_leaf(key) != Self::EMPTY_VALUE {
+            return None;
+        }
+
+        // find the shallowest empty subtree on the way from the root to the leaf; the leaf itself
+        // is such a subtree, so the search always succeeds
+        let empty_depth = (0..=DEPTH)
+            .find(|&depth| {
+                let node = if depth == 0 {
+                    self.root
+                } else {
+                    let shift = (DEPTH - depth) as u32;
+                    let index = NodeIndex::new_unchecked(depth, key.value() >> shift);
+                    self.get_node(index).expect("index is valid for this tree")
+                };
+                node == *EmptySubtreeRoots::entry(DEPTH, depth)
+            })
+            .expect("the leaf is empty");
+
+        let path = self.open(key).path;
+        let path = MerklePath::from(&path[(DEPTH - empty_depth) as usize..]);
+
+        Some(
+            SimpleSmtNonMembershipProof::new(*key, path)
+                .expect("path length is not greater than DEPTH"),
+        )
+    }
+
+    /// Returns the leaves which differ between this tree and `other`, as a list of
+    /// `(index, old_value, new_value)` triples sorted by index.
+    ///
+    /// `old_value` is the value of the leaf in this tree and `new_value` its value in `other`,
+    /// with `None` standing for an empty leaf. Subtrees with the same root in both trees are
+    /// skipped, so the cost of the comparison is proportional to the number of changed leaves.
+    pub fn diff(&self, other: &Self) -> Vec<(u64, Option<Word>, Option<Word>)> {
+        let mut changes = Vec::new();
+        if self.root == other.root {
+            return changes;
+        }
+
+        // traverse the nodes which differ between the trees depth-first, visiting left children
+        // before right ones so that the leaves are reported in order
+        let mut stack = vec![NodeIndex::root()];
+        while let Some(index) = stack.pop() {
+            if index.depth() == DEPTH {
+                let key = index.value();
+                let old_value = self.leaves.get(&key).copied();
+                let new_value = other.leaves.get(&key).copied();
+                changes.push((key, old_value, new_value));
+                continue;
+            }
+
+            let left = index.left_child();
+            let right = index.right_child();
+            for child in [right, left] {
+                if self.get_node(child) != other.get_node(child) {
+                    stack.push(child);
+                }
+            }
+        }
+
+        changes
+    }
+
     // ITERATORS
     // --------------------------------------------------------------------------------------------
 
     /// Returns an iterator over the leaves of this [SimpleSmt].
+    ///
+    /// Only non-empty leaves are yielded, in ascending order of their index.
     pub fn leaves(&self) -> impl Iterator<Item = (u64, &Word)> {
         self.leaves.iter().map(|(i, w)| (*i, w))
     }
 
+    /// Returns an iterator over the index-value pairs of the non-empty leaves of this
+    /// [SimpleSmt], in ascending order of their index.
+    pub fn entries(&self) -> impl Iterator<Item = (u64, Word)> + '_ {
+        self.leaves.iter().map(|(i, w)| (*i, *w))
+    }
+
+    /// Consumes this [SimpleSmt] and returns an iterator over the index-value pairs of its
+    /// non-empty leaves, in ascending order of their index.
+    pub fn into_entries(self) -> impl Iterator<Item = (u64, Word)> {
+        self.leaves.into_iter()
+    }
+
     /// Returns an iterator over the inner nodes of this [SimpleSmt].
     pub fn inner_nodes(&self) -> impl Iterator<Item = InnerNodeInfo> + '_ {
         self.inner_nodes.values().map(|e| InnerNodeInfo {
@@ -187,6 +298,93 @@ impl<const DEPTH: u8> SimpleSmt<DEPTH> {
         <Self as SparseMerkleTree<DEPTH>>::insert(self, key, value)
     }
 
+    /// Removes the leaf at the specified key, returning its previous value.
+    ///
+    /// The leaf is reset to [`EMPTY_WORD`], the nodes between the leaf and the root are restored
+    /// to the roots of the corresponding empty subtrees, and inner nodes which no longer have any
+    /// non-empty descendants are pruned. After the removal, the tree is identical to one in which
+    /// the leaf was never set.
+    pub fn remove_leaf(&mut self, key: LeafIndex<DEPTH>) -> Word {
+        self.insert(key, Self::EMPTY_VALUE)
+    }
+
+    /// Updates the leaves at the specified keys and returns the new root of the tree.
+    ///
+    /// This is equivalent to calling [SimpleSmt::insert] for every entry, but each inner node
+    /// affected by the updates is recomputed only once, regardless of the number of updated leaves
+    /// below it. If the same key appears more than once, the last value is used.
+    ///
+    /// # Errors
+    /// Returns an error if any of the keys is not a valid leaf index for this tree, in which case
+    /// the tree is left unchanged.
+    pub fn update_leaves(
+        &mut self,
+        entries: impl IntoIterator<Item = (u64, Word)>,
+    ) -> Result<RpoDigest, MerkleError> {
+        let mutations = self.compute_mutations(entries)?;
+        self.apply_mutations(mutations)
+            .expect("mutations were computed against the current root");
+
+        Ok(self.root)
+    }
+
+    /// Computes the changes resulting from updating the leaves at the specified keys, without
+    /// modifying this tree.
+    ///
+    /// The returned [SimpleSmtMutationSet] exposes the root the tree would have after the updates,
+    /// and can be committed with [SimpleSmt::apply_mutations]. If the same key appears more than
+    /// once, the last value is used.
+    ///
+    /// # Errors
+    /// Returns an error if any of the keys is not a valid leaf index for this tree.
+    pub fn compute_mutations(
+        &self,
+        entries: impl IntoIterator<Item = (u64, Word)>,
+    ) -> Result<SimpleSmtMutationSet<DEPTH>, MerkleError> {
+        let mut new_pairs = BTreeMap::new();
+        for (key, value) in entries {
+            new_pairs.insert(LeafIndex::<DEPTH>::new(key)?, value);
+        }
+
+        let leaf_hashes =
+            new_pairs.iter().map(|(key, value)| (key.value(), value.into())).collect();
+        let (node_mutations, new_root) = self.compute_node_mutations(leaf_hashes);
+
+        Ok(SimpleSmtMutationSet {
+            old_root: self.root,
+            node_mutations,
+            new_pairs,
+            new_root,
+        })
+    }
+
+    /// Applies the changes computed by [SimpleSmt::compute_mutations] to this tree.
+    ///
+    /// # Errors
+    /// Returns an error if the mutations were computed against a tree with a different root, in
+    /// which case the tree is left unchanged.
+    pub fn apply_mutations(
+        &mut self,
+        mutations: SimpleSmtMutationSet<DEPTH>,
+    ) -> Result<(), MerkleError> {
+        let SimpleSmtMutationSet {
+            old_root,
+            node_mutations,
+            new_pairs,
+            new_root,
+        } = mutations;
+        if old_root != self.root {
+            return Err(MerkleError::StaleMutationSet { expected: old_root, actual: self.root });
+        }
+
+        for (key, value) in new_pairs {
+            self.insert_value(key, value);
+        }
+        self.apply_node_mutations(node_mutations, new_root);
+
+        Ok(())
+    }
+
     /// Inserts a subtree at the specified index. The depth at which the subtree is inserted is
     /// computed as `DEPTH - SUBTREE_DEPTH`.
     ///
@@ -208,6 +406,10 @@ impl<const DEPTH: u8> SimpleSmt<DEPTH> {
         let subtree_root_index =
             NodeIndex::new(subtree_root_insertion_depth, subtree_insertion_index)?;
 
+        // remove the leaves and branch nodes being replaced
+        // --------------
+        self.remove_subtree(subtree_root_index);
+
         // add leaves
         // --------------
 
@@ -246,6 +448,47 @@ impl<const DEPTH: u8> SimpleSmt<DEPTH> {
 
         Ok(self.root)
     }
+
+    // HELPER METHODS
+    // --------------------------------------------------------------------------------------------
+
+    /// Computes all inner nodes and the root of this tree from its leaves.
+    ///
+    /// Must only be called on a tree with no inner nodes.
+    fn build_inner_nodes(&mut self) {
+        let leaves = self.leaves.iter().map(|(&key, &value)| (key, value.into())).collect();
+        self.root = build_inner_nodes::<DEPTH>(leaves, &mut self.inner_nodes);
+    }
+
+    /// Removes all leaves and inner nodes of the subtree rooted at the specified index.
+    ///
+    /// The nodes above the subtree root are left unchanged.
+    fn remove_subtree(&mut self, subtree_root_index: NodeIndex) {
+        // returns the range of positions covered by the subtree at `depth` levels below its root;
+        // u128 is used since a subtree of depth 64 covers 2^64 positions
+        let span = |depth: u8| {
+            let first = (subtree_root_index.value() as u128) << depth;
+            let last = first + (1_u128 << depth) - 1;
+            (first as u64, last as u64)
+        };
+
+        for depth in subtree_root_index.depth()..DEPTH {
+            let (first, last) = span(depth - subtree_root_index.depth());
+            let range =
+                NodeIndex::new_unchecked(depth, first)..=NodeIndex::new_unchecked(depth, last);
+            let indices: Vec<NodeIndex> =
+                self.inner_nodes.range(range).map(|(&index, _)| index).collect();
+            for index in indices {
+                self.inner_nodes.remove(&index);
+            }
+        }
+
+        let (first, last) = span(DEPTH - subtree_root_index.depth());
+        let keys: Vec<u64> = self.leaves.range(first..=last).map(|(&key, _)| key).collect();
+        for key in keys {
+            self.leaves.remove(&key);
+        }
+    }
 }
 
 impl<const DEPTH: u8> SparseMerkleTree<DEPTH> for SimpleSmt<DEPTH> {
@@ -309,3 +552,49 @@ impl<const DEPTH: u8> SparseMerkleTree<DEPTH> for SimpleSmt<DEPTH> {
         (path, leaf).into()
     }
 }
+
+// SERIALIZATION
+// ================================================================================================
+
+impl<const DEPTH: u8> Serializable for SimpleSmt<DEPTH> {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        // Write the depth of the tree, followed by the number of non-empty leaves
+        target.write_u8(DEPTH);
+        target.write_usize(self.leaves.len());
+
+        // Write each (index, value) pair; inner nodes are recomputed on deserialization
+        for (index, value) in self.leaves.iter() {
+            target.write_u64(*index);
+            target.write(value);
+        }
+    }
+}
+
+impl<const DEPTH: u8> Deserializable for SimpleSmt<DEPTH> {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let depth = source.read_u8()?;
+        if depth != DEPTH {
+            return Err(DeserializationError::InvalidValue(format!(
+                "expected a tree of depth {DEPTH}, but found depth {depth}"
+            )));
+        }
+
+        // Read the number of non-empty leaves for this SimpleSmt
+        let num_leaves = source.read_usize()?;
+        let mut entries = Vec::with_capacity(num_leaves);
+
+        for _ in 0..num_leaves {
+            let index = source.read_u64()?;
+            let value: Word = source.read()?;
+            if value == Self::EMPTY_VALUE {
+                return Err(DeserializationError::InvalidValue(format!(
+                    "leaf {index} is serialized with the empty value"
+                )));
+            }
+            entries.push((index, value));
+        }
+
+        Self::with_leaves(entries)
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))
+    }
+}
@@ -0,0 +1,46 @@
+use super::{InnerNode, LeafIndex, NodeIndex, RpoDigest, Word};
+use alloc::collections::BTreeMap;
+
+/// A set of changes to a [`super::SimpleSmt`], computed by
+/// [`super::SimpleSmt::compute_mutations`] and applied by [`super::SimpleSmt::apply_mutations`].
+///
+/// The mutation set records the root of the tree it was computed against, so that it is only
+/// ever applied to the same state of the tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimpleSmtMutationSet<const DEPTH: u8> {
+    /// The root of the tree the mutations were computed against.
+    pub(super) old_root: RpoDigest,
+
+    /// The inner nodes to update, with `None` for the nodes which become roots of empty subtrees
+    /// and must therefore be removed.
+    pub(super) node_mutations: BTreeMap<NodeIndex, Option<InnerNode>>,
+
+    /// The new values of the updated leaves.
+    pub(super) new_pairs: BTreeMap<LeafIndex<DEPTH>, Word>,
+
+    /// The root of the tree once the mutations are applied.
+    pub(super) new_root: RpoDigest,
+}
+
+impl<const DEPTH: u8> SimpleSmtMutationSet<DEPTH> {
+    /// Returns the root of the tree against which these mutations were computed.
+    pub fn old_root(&self) -> RpoDigest {
+        self.old_root
+    }
+
+    /// Returns the root of the tree once these mutations are applied.
+    pub fn root(&self) -> RpoDigest {
+        self.new_root
+    }
+
+    /// Returns an iterator over the leaves updated by these mutations, together with their new
+    /// values.
+    pub fn leaves(&self) -> impl Iterator<Item = (u64, &Word)> {
+        self.new_pairs.iter().map(|(key, value)| (key.value(), value))
+    }
+
+    /// Returns true if these mutations do not update any leaf.
+    pub fn is_empty(&self) -> bool {
+        self.new_pairs.is_empty()
+    }
+}
@@ -0,0 +1,257 @@
+use super::{EmptySubtreeRoots, LeafIndex, MerkleError, MerklePath, RpoDigest, Word};
+use crate::utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+use alloc::string::ToString;
+
+/// A proof which can be used to assert that a leaf of a [`super::SimpleSmt`] holds a given value.
+///
+/// The proof consists of the index of the leaf, the value of the leaf, and the Merkle path from
+/// the leaf to the root of the tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "SimpleSmtProofParts<DEPTH>"))]
+pub struct SimpleSmtProof<const DEPTH: u8> {
+    index: LeafIndex<DEPTH>,
+    value: Word,
+    path: MerklePath,
+}
+
+impl<const DEPTH: u8> SimpleSmtProof<DEPTH> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new instance of [`SimpleSmtProof`] instantiated from the specified leaf index,
+    /// leaf value, and path.
+    ///
+    /// # Errors
+    /// Returns an error if the path length is not `DEPTH`.
+    pub fn new(
+        index: LeafIndex<DEPTH>,
+        value: Word,
+        path: MerklePath,
+    ) -> Result<Self, MerkleError> {
+        if path.depth() != DEPTH {
+            return Err(MerkleError::InvalidDepth { expected: DEPTH, provided: path.depth() });
+        }
+
+        Ok(Self { index, value, path })
+    }
+
+    // PROOF VERIFIER
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns true if a [`super::SimpleSmt`] with the specified root holds the value of this
+    /// proof at the index of this proof.
+    pub fn verify(&self, root: &RpoDigest) -> bool {
+        match self.compute_root() {
+            Ok(computed_root) => computed_root == *root,
+            Err(_) => false,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Computes the root of a [`super::SimpleSmt`] to which this proof resolves.
+    ///
+    /// # Errors
+    /// Returns an error if the index of this proof is not valid for the length of its path.
+    pub fn compute_root(&self) -> Result<RpoDigest, MerkleError> {
+        self.path.compute_root(self.index.value(), self.value.into())
+    }
+
+    /// Returns the index of the leaf opened by this proof.
+    pub fn index(&self) -> LeafIndex<DEPTH> {
+        self.index
+    }
+
+    /// Returns the value of the leaf opened by this proof.
+    pub fn value(&self) -> &Word {
+        &self.value
+    }
+
+    /// Returns the proof's Merkle path.
+    pub fn path(&self) -> &MerklePath {
+        &self.path
+    }
+
+    /// Consume the proof and returns its parts.
+    pub fn into_parts(self) -> (LeafIndex<DEPTH>, Word, MerklePath) {
+        (self.index, self.value, self.path)
+    }
+}
+
+// NON-MEMBERSHIP PROOF
+// ================================================================================================
+
+/// A proof which can be used to assert that a leaf of a [`super::SimpleSmt`] is empty.
+///
+/// Since every subtree of a [`super::SimpleSmt`] which contains only empty leaves hashes to a root
+/// known in advance, the proof consists of the index of the leaf and the Merkle path from the
+/// shallowest empty subtree containing the leaf to the root of the tree. The depth of that subtree
+/// is given by the length of the path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "SimpleSmtNonMembershipProofParts<DEPTH>"))]
+pub struct SimpleSmtNonMembershipProof<const DEPTH: u8> {
+    index: LeafIndex<DEPTH>,
+    path: MerklePath,
+}
+
+impl<const DEPTH: u8> SimpleSmtNonMembershipProof<DEPTH> {
+    // CONSTRUCTOR
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new instance of [`SimpleSmtNonMembershipProof`] instantiated from the specified
+    /// leaf index and the path from the empty subtree containing the leaf to the root.
+    ///
+    /// # Errors
+    /// Returns an error if the path length is greater than `DEPTH`.
+    pub fn new(index: LeafIndex<DEPTH>, path: MerklePath) -> Result<Self, MerkleError> {
+        if path.depth() > DEPTH {
+            return Err(MerkleError::InvalidDepth { expected: DEPTH, provided: path.depth() });
+        }
+
+        Ok(Self { index, path })
+    }
+
+    // PROOF VERIFIER
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns true if the leaf at the index of this proof is empty in a [`super::SimpleSmt`] with
+    /// the specified root.
+    pub fn verify(&self, root: &RpoDigest) -> bool {
+        match self.compute_root() {
+            Ok(computed_root) => computed_root == *root,
+            Err(_) => false,
+        }
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Computes the root of a [`super::SimpleSmt`] to which this proof resolves.
+    ///
+    /// The root is computed from the path and the root of an empty subtree at the depth of the
+    /// path, as given by [`EmptySubtreeRoots`].
+    ///
+    /// # Errors
+    /// Returns an error if the path of this proof is longer than `DEPTH`.
+    pub fn compute_root(&self) -> Result<RpoDigest, MerkleError> {
+        if self.empty_subtree_depth() > DEPTH {
+            return Err(MerkleError::InvalidDepth {
+                expected: DEPTH,
+                provided: self.empty_subtree_depth(),
+            });
+        }
+
+        let empty_root = *EmptySubtreeRoots::entry(DEPTH, self.empty_subtree_depth());
+        self.path.compute_root(self.empty_subtree_index(), empty_root)
+    }
+
+    /// Returns the index of the leaf proven to be empty.
+    pub fn index(&self) -> LeafIndex<DEPTH> {
+        self.index
+    }
+
+    /// Returns the depth of the empty subtree containing the leaf.
+    pub fn empty_subtree_depth(&self) -> u8 {
+        self.path.depth()
+    }
+
+    /// Returns the position of the empty subtree containing the leaf within its depth.
+    pub fn empty_subtree_index(&self) -> u64 {
+        let shift = DEPTH.saturating_sub(self.empty_subtree_depth()) as u32;
+        self.index.value().checked_shr(shift).unwrap_or(0)
+    }
+
+    /// Returns the proof's Merkle path.
+    pub fn path(&self) -> &MerklePath {
+        &self.path
+    }
+
+    /// Consume the proof and returns its parts.
+    pub fn into_parts(self) -> (LeafIndex<DEPTH>, MerklePath) {
+        (self.index, self.path)
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+/// The serialized fields of a [SimpleSmtProof], which are validated by [SimpleSmtProof::new] on
+/// deserialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+pub(super) struct SimpleSmtProofParts<const DEPTH: u8> {
+    pub(super) index: LeafIndex<DEPTH>,
+    pub(super) value: Word,
+    pub(super) path: MerklePath,
+}
+
+#[cfg(feature = "serde")]
+impl<const DEPTH: u8> TryFrom<SimpleSmtProofParts<DEPTH>> for SimpleSmtProof<DEPTH> {
+    type Error = MerkleError;
+
+    fn try_from(parts: SimpleSmtProofParts<DEPTH>) -> Result<Self, Self::Error> {
+        let index = LeafIndex::new(parts.index.value())?;
+        Self::new(index, parts.value, parts.path)
+    }
+}
+
+/// The serialized fields of a [SimpleSmtNonMembershipProof], which are validated by
+/// [SimpleSmtNonMembershipProof::new] on deserialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+pub(super) struct SimpleSmtNonMembershipProofParts<const DEPTH: u8> {
+    pub(super) index: LeafIndex<DEPTH>,
+    pub(super) path: MerklePath,
+}
+
+#[cfg(feature = "serde")]
+impl<const DEPTH: u8> TryFrom<SimpleSmtNonMembershipProofParts<DEPTH>>
+    for SimpleSmtNonMembershipProof<DEPTH>
+{
+    type Error = MerkleError;
+
+    fn try_from(parts: SimpleSmtNonMembershipProofParts<DEPTH>) -> Result<Self, Self::Error> {
+        let index = LeafIndex::new(parts.index.value())?;
+        Self::new(index, parts.path)
+    }
+}
+
+impl<const DEPTH: u8> Serializable for SimpleSmtProof<DEPTH> {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.index.value().write_into(target);
+        self.value.write_into(target);
+        self.path.write_into(target);
+    }
+}
+
+impl<const DEPTH: u8> Deserializable for SimpleSmtProof<DEPTH> {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let index = LeafIndex::new(source.read_u64()?)
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))?;
+        let value: Word = source.read()?;
+        let path = MerklePath::read_from(source)?;
+
+        Self::new(index, value, path)
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))
+    }
+}
+
+impl<const DEPTH: u8> Serializable for SimpleSmtNonMembershipProof<DEPTH> {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        self.index.value().write_into(target);
+        self.path.write_into(target);
+    }
+}
+
+impl<const DEPTH: u8> Deserializable for SimpleSmtNonMembershipProof<DEPTH> {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let index = LeafIndex::new(source.read_u64()?)
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))?;
+        let path = MerklePath::read_from(source)?;
+
+        Self::new(index, path).map_err(|err| DeserializationError::InvalidValue(err.to_string()))
+    }
+}
@@ -1,6 +1,6 @@
 use super::{
     super::{MerkleError, RpoDigest, SimpleSmt},
-    NodeIndex,
+    DuplicatePolicy, NodeIndex, SimpleSmtNonMembershipProof, SimpleSmtProof, TypedSmt,
 };
 use crate::{
     hash::rpo::Rpo256,
@@ -8,7 +8,8 @@ use crate::{
         digests_to_words, int_to_leaf, int_to_node, smt::SparseMerkleTree, EmptySubtreeRoots,
         InnerNodeInfo, LeafIndex, MerkleTree,
     },
-    Word, EMPTY_WORD,
+    utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable},
+    Felt, Word, EMPTY_WORD, ZERO,
 };
 use alloc::vec::Vec;
 
@@ -94,6 +95,21 @@ fn build_contiguous_tree() {
     assert_eq!(tree_with_leaves, tree_with_contiguous_leaves);
 }
 
+#[test]
+fn build_contiguous_tree_of_max_depth() {
+    // only the nodes above the provided values are computed, so a deep tree is built quickly
+    let values: Vec<Word> =
+        (0..100).map(|i| if i % 7 == 0 { EMPTY_WORD } else { int_to_leaf(i) }).collect();
+    let tree = SimpleSmt::<64>::with_contiguous_leaves(values.iter().copied()).unwrap();
+    let expected = SimpleSmt::<64>::with_leaves((0..).zip(values.iter().copied())).unwrap();
+    assert_eq!(tree, expected);
+    assert_eq!(tree.num_leaves(), 85);
+
+    // the number of values cannot exceed the capacity of the tree
+    let result = SimpleSmt::<2>::with_contiguous_leaves(digests_to_words(&VALUES8));
+    assert_eq!(result.unwrap_err(), MerkleError::InvalidNumEntries(4));
+}
+
 #[test]
 fn test_depth2_tree() {
     let tree =
@@ -237,6 +253,176 @@ fn small_tree_opening_is_consistent() {
     }
 }
 
+#[test]
+fn test_simplesmt_prove() {
+    const DEPTH: u8 = 3;
+    let tree =
+        SimpleSmt::<DEPTH>::with_leaves(KEYS8.into_iter().zip(digests_to_words(&VALUES8))).unwrap();
+
+    for key in KEYS8 {
+        let proof = tree.prove(&LeafIndex::new(key).unwrap());
+        assert_eq!(proof.value(), &Word::from(VALUES8[key as usize]));
+        assert!(proof.verify(&tree.root()));
+        assert!(!proof.verify(&RpoDigest::default()));
+
+        let deserialized = SimpleSmtProof::<DEPTH>::read_from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(deserialized, proof);
+    }
+
+    // a proof for a tampered value does not verify
+    let (index, _, path) = tree.prove(&LeafIndex::new(0).unwrap()).into_parts();
+    let proof = SimpleSmtProof::new(index, int_to_leaf(100), path.clone()).unwrap();
+    assert!(!proof.verify(&tree.root()));
+
+    // a path of the wrong length is rejected
+    assert!(SimpleSmtProof::<2>::new(LeafIndex::new(0).unwrap(), EMPTY_WORD, path).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_simplesmt_proof_deserialization_is_validated() {
+    use super::proof::SimpleSmtProofParts;
+
+    const DEPTH: u8 = 3;
+    let tree =
+        SimpleSmt::<DEPTH>::with_leaves(KEYS8.into_iter().zip(digests_to_words(&VALUES8))).unwrap();
+    let (index, value, path) = tree.prove(&LeafIndex::new(5).unwrap()).into_parts();
+
+    let proof = SimpleSmtProof::try_from(SimpleSmtProofParts { index, value, path }).unwrap();
+    assert!(proof.verify(&tree.root()));
+
+    // paths which do not match the depth of the tree are rejected
+    let (index, value, path) = tree.prove(&LeafIndex::new(5).unwrap()).into_parts();
+    let path = path[1..].to_vec().into();
+    assert_eq!(
+        SimpleSmtProof::try_from(SimpleSmtProofParts { index, value, path }),
+        Err(MerkleError::InvalidDepth { expected: DEPTH, provided: DEPTH - 1 })
+    );
+}
+
+#[test]
+fn test_simplesmt_prove_non_membership() {
+    const DEPTH: u8 = 4;
+    let mut tree = SimpleSmt::<DEPTH>::new().unwrap();
+
+    // in an empty tree, the proof opens the root of the tree
+    let key = LeafIndex::new(5).unwrap();
+    let proof = tree.prove_non_membership(&key).unwrap();
+    assert_eq!(proof.empty_subtree_depth(), 0);
+    assert!(proof.verify(&tree.root()));
+
+    // leaves 0 and 1 share the same subtree of depth 3, while leaves 8..16 are all empty
+    tree.insert(LeafIndex::new(0).unwrap(), int_to_leaf(1));
+    assert!(tree.prove_non_membership(&LeafIndex::new(0).unwrap()).is_none());
+
+    let proof = tree.prove_non_membership(&LeafIndex::new(1).unwrap()).unwrap();
+    assert_eq!(proof.empty_subtree_depth(), DEPTH);
+    assert!(proof.verify(&tree.root()));
+
+    let proof = tree.prove_non_membership(&LeafIndex::new(12).unwrap()).unwrap();
+    assert_eq!(proof.empty_subtree_depth(), 1);
+    assert_eq!(proof.empty_subtree_index(), 1);
+    assert!(proof.verify(&tree.root()));
+
+    let deserialized =
+        SimpleSmtNonMembershipProof::<DEPTH>::read_from_bytes(&proof.to_bytes()).unwrap();
+    assert_eq!(deserialized, proof);
+
+    // the proof no longer verifies once the leaf is set
+    tree.insert(LeafIndex::new(12).unwrap(), int_to_leaf(2));
+    assert!(!proof.verify(&tree.root()));
+
+    // a path longer than the tree is rejected
+    let path = tree.open(&LeafIndex::new(1).unwrap()).path;
+    assert!(SimpleSmtNonMembershipProof::<3>::new(LeafIndex::new(1).unwrap(), path).is_err());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_simplesmt_non_membership_proof_deserialization_is_validated() {
+    use super::proof::SimpleSmtNonMembershipProofParts;
+
+    const DEPTH: u8 = 3;
+    let tree = SimpleSmt::<DEPTH>::new().unwrap();
+    let index = LeafIndex::new(5).unwrap();
+    let path = tree.prove_non_membership(&index).unwrap().path().clone();
+
+    let proof =
+        SimpleSmtNonMembershipProof::try_from(SimpleSmtNonMembershipProofParts { index, path })
+            .unwrap();
+    assert!(proof.verify(&tree.root()));
+
+    // paths longer than the depth of the tree are rejected
+    let path = vec![RpoDigest::default(); DEPTH as usize + 1].into();
+    assert_eq!(
+        SimpleSmtNonMembershipProof::try_from(SimpleSmtNonMembershipProofParts { index, path }),
+        Err(MerkleError::InvalidDepth { expected: DEPTH, provided: DEPTH + 1 })
+    );
+}
+
+#[test]
+fn test_simplesmt_entries() {
+    const DEPTH: u8 = 3;
+    let entries = [(6, int_to_leaf(1)), (1, int_to_leaf(2)), (3, EMPTY_WORD)];
+    let mut tree = SimpleSmt::<DEPTH>::with_leaves(entries).unwrap();
+    tree.insert(LeafIndex::new(4).unwrap(), int_to_leaf(3));
+    tree.insert(LeafIndex::new(4).unwrap(), EMPTY_WORD);
+
+    // only non-empty leaves are yielded, in ascending order
+    let expected = vec![(1, int_to_leaf(2)), (6, int_to_leaf(1))];
+    assert_eq!(tree.entries().collect::<Vec<_>>(), expected);
+
+    // the entries can be used to rebuild the tree
+    let rebuilt = SimpleSmt::<DEPTH>::with_leaves(tree.entries()).unwrap();
+    assert_eq!(rebuilt, tree);
+
+    assert_eq!(tree.into_entries().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn test_simplesmt_serialization() {
+    const DEPTH: u8 = 3;
+    let tree =
+        SimpleSmt::<DEPTH>::with_leaves(KEYS8.into_iter().zip(digests_to_words(&VALUES8))).unwrap();
+
+    let bytes = tree.to_bytes();
+    assert_eq!(SimpleSmt::<DEPTH>::read_from_bytes(&bytes).unwrap(), tree);
+
+    let empty = SimpleSmt::<64>::new().unwrap();
+    assert_eq!(SimpleSmt::<64>::read_from_bytes(&empty.to_bytes()).unwrap(), empty);
+
+    // a tree of a different depth is rejected
+    assert!(SimpleSmt::<4>::read_from_bytes(&bytes).is_err());
+}
+
+#[test]
+fn test_simplesmt_diff() {
+    const DEPTH: u8 = 16;
+    let old = SimpleSmt::<DEPTH>::with_leaves([
+        (1, int_to_leaf(1)),
+        (100, int_to_leaf(2)),
+        (1000, int_to_leaf(3)),
+    ])
+    .unwrap();
+    assert!(old.diff(&old.clone()).is_empty());
+
+    let mut new = old.clone();
+    new.insert(LeafIndex::new(1).unwrap(), int_to_leaf(4));
+    new.insert(LeafIndex::new(1000).unwrap(), EMPTY_WORD);
+    new.insert(LeafIndex::new(60000).unwrap(), int_to_leaf(5));
+
+    let expected = vec![
+        (1, Some(int_to_leaf(1)), Some(int_to_leaf(4))),
+        (1000, Some(int_to_leaf(3)), None),
+        (60000, None, Some(int_to_leaf(5))),
+    ];
+    assert_eq!(old.diff(&new), expected);
+
+    // the diff in the other direction swaps old and new values
+    let reversed: Vec<_> = expected.into_iter().map(|(key, old, new)| (key, new, old)).collect();
+    assert_eq!(new.diff(&old), reversed);
+}
+
 #[test]
 fn test_simplesmt_fail_on_duplicates() {
     let values = [
@@ -265,6 +451,38 @@ fn test_simplesmt_fail_on_duplicates() {
     }
 }
 
+#[test]
+fn test_simplesmt_builder_last_write_wins() {
+    let builder = SimpleSmt::<64>::builder().with_duplicate_policy(DuplicatePolicy::LastWriteWins);
+
+    let entries = [
+        (1, int_to_leaf(1)),
+        (5, int_to_leaf(5)),
+        (1, int_to_leaf(2)),
+        (7, int_to_leaf(7)),
+        (7, EMPTY_WORD),
+        (1, int_to_leaf(3)),
+    ];
+    let (smt, duplicates) = builder.build(entries).unwrap();
+    assert_eq!(duplicates, vec![1, 7]);
+
+    let expected =
+        SimpleSmt::<64>::with_leaves([(1, int_to_leaf(3)), (5, int_to_leaf(5))]).unwrap();
+    assert_eq!(smt, expected);
+
+    // a leaf reset to empty and then set again keeps its last value
+    let entries = [(2, int_to_leaf(2)), (2, EMPTY_WORD), (2, int_to_leaf(4))];
+    let (smt, duplicates) = builder.build(entries).unwrap();
+    assert_eq!(duplicates, vec![2]);
+    assert_eq!(smt.get_leaf(&LeafIndex::new(2).unwrap()), int_to_leaf(4));
+
+    // without duplicates, the default builder is equivalent to `with_leaves`
+    let entries = [(1, int_to_leaf(1)), (5, int_to_leaf(5))];
+    let (smt, duplicates) = SimpleSmt::<64>::builder().build(entries).unwrap();
+    assert!(duplicates.is_empty());
+    assert_eq!(smt, SimpleSmt::<64>::with_leaves(entries).unwrap());
+}
+
 #[test]
 fn with_no_duplicates_empty_node() {
     let entries = [(1_u64, int_to_leaf(0)), (5, int_to_leaf(2))];
@@ -443,9 +661,233 @@ fn test_simplesmt_set_subtree_entire_tree() {
     assert_eq!(tree.root(), *EmptySubtreeRoots::entry(DEPTH, 0));
 }
 
+/// Grafting a subtree over existing leaves replaces them, leaving no stale leaves or branch nodes
+#[test]
+fn test_simplesmt_set_subtree_replaces_existing_nodes() {
+    const DEPTH: u8 = 3;
+    let mut tree = SimpleSmt::<DEPTH>::with_leaves([
+        (0, int_to_leaf(1)),
+        (4, int_to_leaf(2)),
+        (5, int_to_leaf(3)),
+        (7, int_to_leaf(4)),
+    ])
+    .unwrap();
+
+    let subtree = SimpleSmt::<2>::with_leaves([(2, int_to_leaf(5))]).unwrap();
+    tree.set_subtree(1, subtree).unwrap();
+
+    let expected =
+        SimpleSmt::<DEPTH>::with_leaves([(0, int_to_leaf(1)), (6, int_to_leaf(5))]).unwrap();
+    assert_eq!(tree, expected);
+
+    // grafting an empty subtree over the entire tree clears it
+    tree.set_subtree(0, SimpleSmt::<DEPTH>::new().unwrap()).unwrap();
+    assert_eq!(tree, SimpleSmt::<DEPTH>::new().unwrap());
+}
+
+#[test]
+fn test_simplesmt_remove_leaf() {
+    const DEPTH: u8 = 3;
+    let mut tree =
+        SimpleSmt::<DEPTH>::with_leaves([(1, int_to_leaf(1)), (6, int_to_leaf(6))]).unwrap();
+    let expected = SimpleSmt::<DEPTH>::with_leaves([(1, int_to_leaf(1))]).unwrap();
+
+    let old_value = tree.remove_leaf(LeafIndex::new(6).unwrap());
+    assert_eq!(old_value, int_to_leaf(6));
+    assert_eq!(tree, expected);
+    assert_eq!(tree.inner_nodes().count(), DEPTH as usize);
+
+    // removing an empty leaf is a no-op
+    assert_eq!(tree.remove_leaf(LeafIndex::new(6).unwrap()), EMPTY_WORD);
+    assert_eq!(tree, expected);
+
+    // removing the last leaf prunes all inner nodes
+    assert_eq!(tree.remove_leaf(LeafIndex::new(1).unwrap()), int_to_leaf(1));
+    assert_eq!(tree, SimpleSmt::<DEPTH>::new().unwrap());
+    assert_eq!(tree.inner_nodes().count(), 0);
+}
+
+#[test]
+fn test_simplesmt_with_leaves_matches_inserts() {
+    const DEPTH: u8 = 20;
+
+    // keys are spread across the tree, with every tenth leaf set to the empty word
+    let entries: Vec<(u64, Word)> = (0..1000_u64)
+        .map(|i| {
+            let value = if i % 10 == 0 { EMPTY_WORD } else { int_to_leaf(i) };
+            ((i * 7919) % (1 << DEPTH), value)
+        })
+        .collect();
+
+    let tree = SimpleSmt::<DEPTH>::with_leaves(entries.clone()).unwrap();
+
+    let mut expected = SimpleSmt::<DEPTH>::new().unwrap();
+    for (key, value) in entries {
+        expected.insert(LeafIndex::<DEPTH>::new(key).unwrap(), value);
+    }
+
+    assert_eq!(tree.root(), expected.root());
+    assert_eq!(tree, expected);
+}
+
+#[test]
+fn test_simplesmt_update_leaves() {
+    const DEPTH: u8 = 3;
+    let initial = KEYS8.into_iter().zip(digests_to_words(&VALUES8));
+    let mut tree = SimpleSmt::<DEPTH>::with_leaves(initial.clone()).unwrap();
+    let mut expected = SimpleSmt::<DEPTH>::with_leaves(initial).unwrap();
+
+    // updates include siblings, a leaf cleared to the empty word, and a repeated key
+    let updates = [
+        (0, int_to_leaf(10)),
+        (1, int_to_leaf(11)),
+        (5, EMPTY_WORD),
+        (6, int_to_leaf(12)),
+        (6, int_to_leaf(13)),
+    ];
+    for (key, value) in updates {
+        expected.insert(LeafIndex::<DEPTH>::new(key).unwrap(), value);
+    }
+
+    let root = tree.update_leaves(updates).unwrap();
+    assert_eq!(root, expected.root());
+    assert_eq!(tree, expected);
+
+    // clearing every leaf prunes all inner nodes
+    let root = tree.update_leaves(KEYS8.into_iter().map(|key| (key, EMPTY_WORD))).unwrap();
+    assert_eq!(root, *EmptySubtreeRoots::entry(DEPTH, 0));
+    assert_eq!(tree.inner_nodes().count(), 0);
+    assert_eq!(tree.num_leaves(), 0);
+}
+
+#[test]
+fn test_simplesmt_update_leaves_invalid_key() {
+    let mut tree =
+        SimpleSmt::<2>::with_leaves(KEYS4.into_iter().zip(digests_to_words(&VALUES4))).unwrap();
+    let tree_before_update = tree.clone();
+
+    // key 4 is out of bounds for a tree of depth 2, so none of the updates are applied
+    let result = tree.update_leaves([(0, int_to_leaf(5)), (4, int_to_leaf(6))]);
+    assert!(result.is_err());
+    assert_eq!(tree, tree_before_update);
+}
+
+#[test]
+fn test_simplesmt_compute_and_apply_mutations() {
+    const DEPTH: u8 = 3;
+    let mut tree =
+        SimpleSmt::<DEPTH>::with_leaves(KEYS8.into_iter().zip(digests_to_words(&VALUES8))).unwrap();
+    let tree_before_update = tree.clone();
+
+    let updates = [(0, int_to_leaf(10)), (5, EMPTY_WORD), (6, int_to_leaf(12))];
+
+    // the expected tree is obtained by inserting the updates one by one, and agrees with a tree
+    // built from the resulting leaves directly
+    let mut expected = tree.clone();
+    for (key, value) in updates {
+        expected.insert(LeafIndex::new(key).unwrap(), value);
+    }
+    let mut leaves = digests_to_words(&VALUES8);
+    leaves[0] = int_to_leaf(10);
+    leaves[5] = EMPTY_WORD;
+    leaves[6] = int_to_leaf(12);
+    let rebuilt = SimpleSmt::<DEPTH>::with_leaves(KEYS8.into_iter().zip(leaves)).unwrap();
+    assert_eq!(expected.root(), rebuilt.root());
+
+    // computing the mutations predicts the new root without modifying the tree
+    let mutations = tree.compute_mutations(updates).unwrap();
+    assert_eq!(mutations.old_root(), tree.root());
+    assert_eq!(mutations.root(), expected.root());
+    assert_eq!(mutations.leaves().count(), 3);
+    assert_eq!(tree, tree_before_update);
+
+    tree.apply_mutations(mutations.clone()).unwrap();
+    assert_eq!(tree, expected);
+
+    // mutations computed against a different root are rejected
+    let result = tree.apply_mutations(mutations);
+    assert_eq!(
+        result,
+        Err(MerkleError::StaleMutationSet {
+            expected: tree_before_update.root(),
+            actual: expected.root()
+        })
+    );
+    assert_eq!(tree, expected);
+
+    // an empty set of mutations keeps the root
+    let mutations = tree.compute_mutations([]).unwrap();
+    assert!(mutations.is_empty());
+    assert_eq!(mutations.root(), tree.root());
+}
+
+#[test]
+fn test_typed_smt() {
+    const DEPTH: u8 = 3;
+    let mut tree = TypedSmt::<DEPTH, Account>::with_entries([
+        (1, Account::new(7, 10)),
+        (4, Account::new(8, 0)),
+    ])
+    .unwrap();
+
+    // the tree commits to the word form of each value
+    let expected = SimpleSmt::<DEPTH>::with_leaves([
+        (1, Account::new(7, 10).into()),
+        (4, Account::new(8, 0).into()),
+    ])
+    .unwrap();
+    assert_eq!(tree.root(), expected.root());
+    assert_eq!(tree.get(&LeafIndex::new(1).unwrap()), Some(&Account::new(7, 10)));
+    assert_eq!(tree.get(&LeafIndex::new(2).unwrap()), None);
+
+    let key = LeafIndex::new(4).unwrap();
+    assert_eq!(tree.insert(key, Account::new(8, 5)), Some(Account::new(8, 0)));
+    assert!(tree.prove(&key).verify(&tree.root()));
+
+    // a value converting to the empty word removes the leaf
+    assert_eq!(tree.insert(key, Account::new(0, 0)), Some(Account::new(8, 5)));
+    assert_eq!(tree.num_leaves(), 1);
+    assert_eq!(tree.root(), tree.as_simple_smt().root());
+
+    let bytes = tree.to_bytes();
+    assert_eq!(TypedSmt::<DEPTH, Account>::read_from_bytes(&bytes).unwrap(), tree);
+}
+
 // HELPER FUNCTIONS
 // --------------------------------------------------------------------------------------------
 
+/// A structured leaf value used to test [TypedSmt].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Account {
+    id: u64,
+    balance: u64,
+}
+
+impl Account {
+    fn new(id: u64, balance: u64) -> Self {
+        Self { id, balance }
+    }
+}
+
+impl From<Account> for Word {
+    fn from(account: Account) -> Self {
+        [Felt::new(account.id), Felt::new(account.balance), ZERO, ZERO]
+    }
+}
+
+impl Serializable for Account {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_u64(self.id);
+        target.write_u64(self.balance);
+    }
+}
+
+impl Deserializable for Account {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        Ok(Self::new(source.read_u64()?, source.read_u64()?))
+    }
+}
+
 fn compute_internal_nodes() -> (RpoDigest, RpoDigest, RpoDigest) {
     let node2 = Rpo256::merge(&[VALUES4[0], VALUES4[1]]);
     let node3 = Rpo256::merge(&[VALUES4[2], VALUES4[3]]);
@@ -0,0 +1,178 @@
+use super::{
+    LeafIndex, MerkleError, RpoDigest, SimpleSmt, SimpleSmtProof, ValuePath, Word, EMPTY_WORD,
+};
+use crate::utils::{ByteReader, ByteWriter, Deserializable, DeserializationError, Serializable};
+use alloc::{collections::BTreeMap, string::ToString, vec::Vec};
+
+// TYPED SPARSE MERKLE TREE
+// ================================================================================================
+
+/// A [SimpleSmt] whose leaves hold structured values of type `V`.
+///
+/// Each value is committed to in the tree by its conversion into a [Word], while the values
+/// themselves are kept alongside the tree, so that they can be retrieved and serialized as they
+/// were inserted. Since a value which converts into [EMPTY_WORD] is indistinguishable from an
+/// empty leaf, inserting such a value removes the leaf.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedSmt<const DEPTH: u8, V> {
+    tree: SimpleSmt<DEPTH>,
+    values: BTreeMap<u64, V>,
+}
+
+impl<const DEPTH: u8, V: Clone + Into<Word>> TypedSmt<DEPTH, V> {
+    // CONSTRUCTORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns a new [TypedSmt] with all leaves empty.
+    ///
+    /// # Errors
+    /// Returns an error if DEPTH is 0 or is greater than 64.
+    pub fn new() -> Result<Self, MerkleError> {
+        Ok(Self {
+            tree: SimpleSmt::new()?,
+            values: BTreeMap::new(),
+        })
+    }
+
+    /// Returns a new [TypedSmt] instantiated with leaves set as specified by the provided entries.
+    ///
+    /// # Errors
+    /// Returns an error if:
+    /// - If the depth is 0 or is greater than 64.
+    /// - The number of entries exceeds the maximum tree capacity, that is 2^{depth}.
+    /// - The provided entries contain multiple values for the same key.
+    pub fn with_entries(entries: impl IntoIterator<Item = (u64, V)>) -> Result<Self, MerkleError> {
+        let entries: Vec<(u64, V)> = entries.into_iter().collect();
+        let tree = SimpleSmt::with_leaves(
+            entries.iter().map(|(key, value)| (*key, value.clone().into())),
+        )?;
+
+        let values = entries
+            .into_iter()
+            .filter(|(_, value)| Into::<Word>::into(value.clone()) != EMPTY_WORD)
+            .collect();
+
+        Ok(Self { tree, values })
+    }
+
+    // PUBLIC ACCESSORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns the depth of the tree
+    pub const fn depth(&self) -> u8 {
+        DEPTH
+    }
+
+    /// Returns the root of the tree
+    pub fn root(&self) -> RpoDigest {
+        self.tree.root()
+    }
+
+    /// Returns the number of non-empty leaves in this tree.
+    pub fn num_leaves(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns the value at the specified index, or `None` if the leaf is empty.
+    pub fn get(&self, key: &LeafIndex<DEPTH>) -> Option<&V> {
+        self.values.get(&key.value())
+    }
+
+    /// Returns an opening of the leaf associated with `key`.
+    ///
+    /// The opened value is the [Word] into which the value of the leaf converts.
+    pub fn open(&self, key: &LeafIndex<DEPTH>) -> ValuePath {
+        self.tree.open(key)
+    }
+
+    /// Returns a self-contained proof of the leaf associated with `key`.
+    pub fn prove(&self, key: &LeafIndex<DEPTH>) -> SimpleSmtProof<DEPTH> {
+        self.tree.prove(key)
+    }
+
+    /// Returns the underlying [SimpleSmt], whose leaves hold the [Word] conversions of the values
+    /// of this tree.
+    pub fn as_simple_smt(&self) -> &SimpleSmt<DEPTH> {
+        &self.tree
+    }
+
+    // ITERATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Returns an iterator over the index-value pairs of the non-empty leaves of this tree, in
+    /// ascending order of their index.
+    pub fn entries(&self) -> impl Iterator<Item = (u64, &V)> {
+        self.values.iter().map(|(key, value)| (*key, value))
+    }
+
+    // STATE MUTATORS
+    // --------------------------------------------------------------------------------------------
+
+    /// Inserts a value at the specified key, returning the previous value associated with that
+    /// key, if any.
+    pub fn insert(&mut self, key: LeafIndex<DEPTH>, value: V) -> Option<V> {
+        let word: Word = value.clone().into();
+        self.tree.insert(key, word);
+
+        if word == EMPTY_WORD {
+            self.values.remove(&key.value())
+        } else {
+            self.values.insert(key.value(), value)
+        }
+    }
+
+    /// Removes the leaf at the specified key, returning its previous value, if any.
+    pub fn remove(&mut self, key: LeafIndex<DEPTH>) -> Option<V> {
+        self.tree.remove_leaf(key);
+        self.values.remove(&key.value())
+    }
+}
+
+// SERIALIZATION
+// ================================================================================================
+
+impl<const DEPTH: u8, V: Serializable> Serializable for TypedSmt<DEPTH, V> {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        // Write the depth of the tree, followed by the number of non-empty leaves
+        target.write_u8(DEPTH);
+        target.write_usize(self.values.len());
+
+        // Write each (index, value) pair; the tree is rebuilt on deserialization
+        for (index, value) in self.values.iter() {
+            target.write_u64(*index);
+            value.write_into(target);
+        }
+    }
+}
+
+impl<const DEPTH: u8, V> Deserializable for TypedSmt<DEPTH, V>
+where
+    V: Clone + Into<Word> + Deserializable,
+{
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let depth = source.read_u8()?;
+        if depth != DEPTH {
+            return Err(DeserializationError::InvalidValue(format!(
+                "expected a tree of depth {DEPTH}, but found depth {depth}"
+            )));
+        }
+
+        let num_leaves = source.read_usize()?;
+        let mut entries = Vec::with_capacity(num_leaves);
+        for _ in 0..num_leaves {
+            let index = source.read_u64()?;
+            let value = V::read_from(source)?;
+            entries.push((index, value));
+        }
+
+        let tree = Self::with_entries(entries)
+            .map_err(|err| DeserializationError::InvalidValue(err.to_string()))?;
+        if tree.num_leaves() != num_leaves {
+            return Err(DeserializationError::InvalidValue(
+                "a leaf is serialized with a value converting to the empty word".to_string(),
+            ));
+        }
+
+        Ok(tree)
+    }
+}
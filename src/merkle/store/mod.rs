@@ -1,9 +1,13 @@
-use alloc::{collections::BTreeMap, vec::Vec};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
 use core::borrow::Borrow;
 
 use super::{
-    mmr::Mmr, EmptySubtreeRoots, InnerNodeInfo, MerkleError, MerklePath, MerkleTree, NodeIndex,
-    PartialMerkleTree, RootPath, Rpo256, RpoDigest, SimpleSmt, Smt, ValuePath,
+    mmr::Mmr, EmptySubtreeRoots, Felt, InnerNodeInfo, MerkleError, MerklePath, MerkleTree,
+    MultiProof, NodeIndex, PartialMerkleTree, RootPath, Rpo256, RpoDigest, SimpleSmt, Smt,
+    ValuePath, Word, MAX_EMPTY_SUBTREE_DEPTH, MAX_TREE_DEPTH,
 };
 use crate::utils::{
     collections::{KvMap, RecordingMap},
@@ -114,6 +118,17 @@ impl<T: KvMap<RpoDigest, StoreNode>> MerkleStore<T> {
         MerkleStore { nodes }
     }
 
+    /// Creates an empty `MerkleStore` instance with space for at least `capacity` nodes in
+    /// addition to the roots of empty subtrees the store is pre-populated with.
+    ///
+    /// The capacity hint is forwarded to the backing storage, and is ignored by storages which
+    /// cannot be pre-allocated.
+    pub fn with_capacity(capacity: usize) -> MerkleStore<T> {
+        let mut nodes = T::with_capacity(capacity + MAX_EMPTY_SUBTREE_DEPTH as usize);
+        nodes.extend(empty_hashes());
+        MerkleStore { nodes }
+    }
+
     // PUBLIC ACCESSORS
     // --------------------------------------------------------------------------------------------
 
@@ -122,6 +137,24 @@ impl<T: KvMap<RpoDigest, StoreNode>> MerkleStore<T> {
         self.nodes.len()
     }
 
+    /// Returns a digest committing to all the nodes of this store.
+    ///
+    /// The nodes are sorted by their digest before being hashed, so the fingerprint doesn't depend
+    /// on the order in which they were inserted nor on the backing storage. Two stores holding the
+    /// same set of nodes always have the same fingerprint.
+    pub fn fingerprint(&self) -> RpoDigest {
+        let mut nodes: Vec<(&RpoDigest, &StoreNode)> = self.nodes.iter().collect();
+        nodes.sort_unstable_by_key(|(digest, _)| *digest);
+
+        let elements: Vec<Felt> = nodes
+            .into_iter()
+            .flat_map(|(digest, node)| [*digest, node.left, node.right])
+            .flat_map(Word::from)
+            .collect();
+
+        Rpo256::hash_elements(&elements)
+    }
+
     /// Returns the node at `index` rooted on the tree `root`.
     ///
     /// # Errors
@@ -180,6 +213,181 @@ impl<T: KvMap<RpoDigest, StoreNode>> MerkleStore<T> {
         Ok(ValuePath::new(hash, MerklePath::new(path)))
     }
 
+    /// Returns the opening of the deepest node which can be reached while traversing from `root`
+    /// towards `index`, together with the depth of that node.
+    ///
+    /// Unlike [MerkleStore::get_path], this method doesn't fail when a node is missing from the
+    /// store. The traversal stops when either:
+    /// - `index` is reached, in which case the returned depth is `index.depth()`.
+    /// - a node is not present in the store.
+    /// - a node is the root of an empty subtree, assuming the leaves of the tree are at the depth
+    ///   of `index`. In this case, the remainder of the path is composed of empty subtree roots
+    ///   and can be reconstructed with [EmptySubtreeRoots].
+    ///
+    /// The returned path starts at the sibling of the reached node and has a length equal to the
+    /// returned depth.
+    ///
+    /// # Errors
+    /// This method can return the following errors:
+    /// - `RootNotInStore` if the `root` is not present in the store.
+    pub fn get_path_partial(
+        &self,
+        root: RpoDigest,
+        index: NodeIndex,
+    ) -> Result<(ValuePath, u8), MerkleError> {
+        let empty = EmptySubtreeRoots::empty_hashes(index.depth());
+        let mut hash = root;
+        let mut path = Vec::with_capacity(index.depth().into());
+
+        // the root must be in the store, otherwise there is nothing to open
+        self.nodes.get(&hash).ok_or(MerkleError::RootNotInStore(hash))?;
+
+        let mut depth = 0;
+        while depth < index.depth() {
+            // stop as soon as the remainder of the path is known to be empty
+            if hash == empty[depth as usize] {
+                break;
+            }
+
+            let node = match self.nodes.get(&hash) {
+                Some(node) => node,
+                None => break,
+            };
+
+            let bit = (index.value() >> (index.depth() - depth - 1)) & 1;
+            hash = if bit == 0 {
+                path.push(node.right);
+                node.left
+            } else {
+                path.push(node.left);
+                node.right
+            };
+            depth += 1;
+        }
+
+        // the path is computed from root to leaf, so it must be reversed
+        path.reverse();
+
+        Ok((ValuePath::new(hash, MerklePath::new(path)), depth))
+    }
+
+    /// Returns a [MultiProof] opening all the nodes at the specified `indices` to `root`.
+    ///
+    /// Sibling nodes shared by several openings are included in the proof only once, and nodes
+    /// which can be computed from the opened nodes are omitted altogether. The values of the
+    /// opened nodes are not part of the proof and can be retrieved via [MerkleStore::get_node].
+    ///
+    /// # Errors
+    /// This method can return the following errors:
+    /// - `RootNotInStore` if the `root` is not present in the store.
+    /// - `NodeNotInStore` if a node needed to traverse from `root` to any of the `indices` is not
+    ///   present in the store.
+    pub fn get_multiproof(
+        &self,
+        root: RpoDigest,
+        indices: impl IntoIterator<Item = NodeIndex>,
+    ) -> Result<MultiProof, MerkleError> {
+        let mut siblings = BTreeMap::new();
+        let mut computable = BTreeSet::new();
+
+        for mut index in indices {
+            let ValuePath { path, .. } = self.get_path(root, index)?;
+
+            // every node from the opened node up to the root can be computed by the verifier
+            computable.insert(index);
+            for sibling in path {
+                siblings.insert(index.sibling(), sibling);
+                index.move_up();
+                computable.insert(index);
+            }
+        }
+
+        Ok(MultiProof::new(
+            siblings.into_iter().filter(|(index, _)| !computable.contains(index)),
+        ))
+    }
+
+    /// Returns a [PartialMerkleTree] with the nodes at the specified `indices` of the tree with
+    /// the specified `root`, together with their openings.
+    ///
+    /// The indices may be at different depths. This is the counterpart of building a
+    /// [MerkleStore] from a [PartialMerkleTree].
+    ///
+    /// # Errors
+    /// This method can return the following errors:
+    /// - `RootNotInStore` if the `root` is not present in the store.
+    /// - `NodeNotInStore` if a node needed to traverse from `root` to any of the `indices` is not
+    ///   present in the store.
+    /// - Any error returned by [PartialMerkleTree::add_path], e.g. if one of the `indices` is the
+    ///   root of the tree.
+    pub fn get_partial_tree(
+        &self,
+        root: RpoDigest,
+        indices: impl IntoIterator<Item = NodeIndex>,
+    ) -> Result<PartialMerkleTree, MerkleError> {
+        let mut tree = PartialMerkleTree::new();
+        for index in indices {
+            let ValuePath { value, path } = self.get_path(root, index)?;
+            tree.add_path(index.value(), value, path)?;
+        }
+
+        Ok(tree)
+    }
+
+    /// Returns the root which would result from setting the nodes at the specified indices of the
+    /// tree with the specified `root` to the specified values, without modifying the store.
+    ///
+    /// The updates are applied in order, as if by successive calls to [MerkleStore::set_node],
+    /// each one starting from the root produced by the previous one. The nodes created by the
+    /// updates are kept in a temporary overlay which is discarded when this method returns.
+    ///
+    /// # Errors
+    /// This method can return the following errors:
+    /// - `RootNotInStore` if the `root` is not present in the store.
+    /// - `NodeNotInStore` if a node needed to traverse from the current root to the index of an
+    ///   update is neither present in the store nor created by a previous update.
+    pub fn compute_root_after(
+        &self,
+        root: RpoDigest,
+        updates: impl IntoIterator<Item = (NodeIndex, RpoDigest)>,
+    ) -> Result<RpoDigest, MerkleError> {
+        let mut overlay: BTreeMap<RpoDigest, StoreNode> = BTreeMap::new();
+        let mut root = root;
+
+        for (index, value) in updates {
+            let get_node = |hash: &RpoDigest| overlay.get(hash).or_else(|| self.nodes.get(hash));
+
+            // open the node at `index` against the current root
+            get_node(&root).ok_or(MerkleError::RootNotInStore(root))?;
+            let mut hash = root;
+            let mut path = Vec::with_capacity(index.depth().into());
+            for i in (0..index.depth()).rev() {
+                let node = get_node(&hash).ok_or(MerkleError::NodeNotInStore(hash, index))?;
+
+                let bit = (index.value() >> i) & 1;
+                hash = if bit == 0 {
+                    path.push(node.right);
+                    node.left
+                } else {
+                    path.push(node.left);
+                    node.right
+                }
+            }
+            path.reverse();
+
+            // record the nodes of the updated path in the overlay
+            if hash != value {
+                let nodes = MerklePath::new(path).inner_nodes(index.value(), value)?;
+                root = nodes.fold(value, |_, node| {
+                    overlay.insert(node.value, StoreNode { left: node.left, right: node.right });
+                    node.value
+                });
+            }
+        }
+
+        Ok(root)
+    }
+
     // LEAF TRAVERSAL
     // --------------------------------------------------------------------------------------------
 
@@ -192,7 +400,7 @@ impl<T: KvMap<RpoDigest, StoreNode>> MerkleStore<T> {
     /// # Errors
     /// Will return an error if:
     /// - The provided root is not found.
-    /// - The provided `tree_depth` is greater than 64.
+    /// - The provided `tree_depth` is greater than [MAX_TREE_DEPTH].
     /// - The provided `index` is not valid for a depth equivalent to `tree_depth`.
     /// - No leaf or an empty node was found while traversing the tree down to `tree_depth`.
     pub fn get_leaf_depth(
@@ -202,7 +410,7 @@ impl<T: KvMap<RpoDigest, StoreNode>> MerkleStore<T> {
         index: u64,
     ) -> Result<u8, MerkleError> {
         // validate depth and index
-        if tree_depth > 64 {
+        if tree_depth > MAX_TREE_DEPTH {
             return Err(MerkleError::DepthTooBig(tree_depth as u64));
         }
         NodeIndex::new(tree_depth, index)?;
@@ -251,7 +459,7 @@ impl<T: KvMap<RpoDigest, StoreNode>> MerkleStore<T> {
     /// # Errors
     /// Will return an error if:
     /// - The provided root is not found.
-    /// - The provided `tree_depth` is greater than 64.
+    /// - The provided `tree_depth` is greater than [MAX_TREE_DEPTH].
     /// - The provided `root_index` has depth greater than `tree_depth`.
     /// - A lone node at depth `tree_depth` is not a leaf node.
     pub fn find_lone_leaf(
@@ -260,12 +468,10 @@ impl<T: KvMap<RpoDigest, StoreNode>> MerkleStore<T> {
         root_index: NodeIndex,
         tree_depth: u8,
     ) -> Result<Option<(NodeIndex, RpoDigest)>, MerkleError> {
-        // we set max depth at u64::BITS as this is the largest meaningful value for a 64-bit index
-        const MAX_DEPTH: u8 = u64::BITS as u8;
-        if tree_depth > MAX_DEPTH {
+        if tree_depth > MAX_TREE_DEPTH {
             return Err(MerkleError::DepthTooBig(tree_depth as u64));
         }
-        let empty = EmptySubtreeRoots::empty_hashes(MAX_DEPTH);
+        let empty = EmptySubtreeRoots::empty_hashes(MAX_TREE_DEPTH);
 
         let mut node = root;
         if !self.nodes.contains_key(&node) {
@@ -376,6 +582,14 @@ impl<T: KvMap<RpoDigest, StoreNode>> MerkleStore<T> {
     // STATE MUTATORS
     // --------------------------------------------------------------------------------------------
 
+    /// Reserves space for at least `additional` more nodes in the store.
+    ///
+    /// The capacity hint is forwarded to the backing storage, and is ignored by storages which
+    /// cannot be pre-allocated.
+    pub fn reserve(&mut self, additional: usize) {
+        self.nodes.reserve(additional);
+    }
+
     /// Adds all the nodes of a Merkle path represented by `path`, opening to `node`. Returns the
     /// new root.
     ///
@@ -481,6 +695,100 @@ impl<T: KvMap<RpoDigest, StoreNode>> MerkleStore<T> {
     }
 }
 
+// WITNESS DEDUPLICATION
+// ================================================================================================
+
+/// References to the nodes of a single witness after deduplication with [dedupe_witnesses].
+///
+/// The nodes which are shared with other witnesses are referenced by their digest and must be
+/// looked up in the shared store; the nodes which are unique to this witness are kept inline.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WitnessRefs {
+    shared: Vec<RpoDigest>,
+    unique: Vec<(RpoDigest, StoreNode)>,
+}
+
+impl WitnessRefs {
+    /// Returns the digests of the nodes of this witness which live in the shared store.
+    pub fn shared_nodes(&self) -> &[RpoDigest] {
+        &self.shared
+    }
+
+    /// Returns an iterator over the nodes which are unique to this witness.
+    pub fn unique_nodes(&self) -> impl Iterator<Item = InnerNodeInfo> + '_ {
+        self.unique.iter().map(|(value, node)| InnerNodeInfo {
+            value: *value,
+            left: node.left,
+            right: node.right,
+        })
+    }
+
+    /// Rebuilds the original witness from these references and the shared store.
+    ///
+    /// # Errors
+    /// Returns `NodeHashNotInStore` if a node referenced by this witness is not in `shared`.
+    pub fn to_store<T: KvMap<RpoDigest, StoreNode>>(
+        &self,
+        shared: &MerkleStore<T>,
+    ) -> Result<MerkleStore<T>, MerkleError> {
+        let mut nodes = Vec::with_capacity(self.shared.len() + self.unique.len());
+        for digest in self.shared.iter() {
+            let node = shared.nodes.get(digest).ok_or(MerkleError::NodeHashNotInStore(*digest))?;
+            nodes.push((*digest, *node));
+        }
+        nodes.extend(self.unique.iter().copied());
+
+        Ok(nodes.into_iter().collect())
+    }
+}
+
+/// Factors the nodes which are common to several witnesses into a single shared store.
+///
+/// Returns the shared store, containing every node present in at least two of the provided
+/// witnesses, together with a [WitnessRefs] for each witness, in the order they were provided.
+/// Each [WitnessRefs] keeps only the nodes unique to its witness, and references the rest.
+///
+/// The roots of empty subtrees are part of every [MerkleStore] and are never referenced.
+pub fn dedupe_witnesses<'a, T, I>(witnesses: I) -> (MerkleStore<T>, Vec<WitnessRefs>)
+where
+    T: KvMap<RpoDigest, StoreNode> + 'a,
+    I: IntoIterator<Item = &'a MerkleStore<T>>,
+{
+    let empty: BTreeSet<RpoDigest> = empty_hashes().into_iter().map(|(digest, _)| digest).collect();
+    let witnesses: Vec<&MerkleStore<T>> = witnesses.into_iter().collect();
+
+    // count the number of witnesses each node appears in; a store holds each node at most once
+    let mut counts: BTreeMap<RpoDigest, (StoreNode, usize)> = BTreeMap::new();
+    for witness in witnesses.iter() {
+        for (digest, node) in witness.nodes.iter().filter(|(digest, _)| !empty.contains(digest)) {
+            counts.entry(*digest).or_insert((*node, 0)).1 += 1;
+        }
+    }
+
+    let shared = counts
+        .iter()
+        .filter(|(_, (_, count))| *count > 1)
+        .map(|(digest, (node, _))| (*digest, *node))
+        .collect();
+
+    let refs = witnesses
+        .iter()
+        .map(|witness| {
+            let mut refs = WitnessRefs::default();
+            for (digest, node) in witness.nodes.iter().filter(|(digest, _)| !empty.contains(digest))
+            {
+                match counts.get(digest) {
+                    Some((_, count)) if *count > 1 => refs.shared.push(*digest),
+                    _ => refs.unique.push((*digest, *node)),
+                }
+            }
+            refs
+        })
+        .collect();
+
+    (shared, refs)
+}
+
 // CONVERSIONS
 // ================================================================================================
 
@@ -569,6 +877,36 @@ impl Deserializable for StoreNode {
     }
 }
 
+impl Serializable for WitnessRefs {
+    fn write_into<W: ByteWriter>(&self, target: &mut W) {
+        target.write_usize(self.shared.len());
+        target.write_many(&self.shared);
+
+        target.write_usize(self.unique.len());
+        for (digest, node) in self.unique.iter() {
+            digest.write_into(target);
+            node.write_into(target);
+        }
+    }
+}
+
+impl Deserializable for WitnessRefs {
+    fn read_from<R: ByteReader>(source: &mut R) -> Result<Self, DeserializationError> {
+        let num_shared = source.read_usize()?;
+        let shared = source.read_many::<RpoDigest>(num_shared)?;
+
+        let num_unique = source.read_usize()?;
+        let mut unique = Vec::with_capacity(num_unique);
+        for _ in 0..num_unique {
+            let digest = RpoDigest::read_from(source)?;
+            let node = StoreNode::read_from(source)?;
+            unique.push((digest, node));
+        }
+
+        Ok(Self { shared, unique })
+    }
+}
+
 impl<T: KvMap<RpoDigest, StoreNode>> Serializable for MerkleStore<T> {
     fn write_into<W: ByteWriter>(&self, target: &mut W) {
         target.write_u64(self.nodes.len() as u64);
@@ -598,9 +936,10 @@ impl<T: KvMap<RpoDigest, StoreNode>> Deserializable for MerkleStore<T> {
 // HELPER FUNCTIONS
 // ================================================================================================
 
-/// Creates empty hashes for all the subtrees of a tree with a max depth of 255.
+/// Creates empty hashes for all the subtrees of a tree with a max depth of
+/// [MAX_EMPTY_SUBTREE_DEPTH].
 fn empty_hashes() -> impl IntoIterator<Item = (RpoDigest, StoreNode)> {
-    let subtrees = EmptySubtreeRoots::empty_hashes(255);
+    let subtrees = EmptySubtreeRoots::empty_hashes(MAX_EMPTY_SUBTREE_DEPTH);
     subtrees
         .iter()
         .rev()
@@ -610,7 +949,8 @@ fn empty_hashes() -> impl IntoIterator<Item = (RpoDigest, StoreNode)> {
 }
 
 /// Consumes an iterator of [InnerNodeInfo] and returns an iterator of `(value, node)` tuples
-/// which includes the nodes associate with roots of empty subtrees up to a depth of 255.
+/// which includes the nodes associate with roots of empty subtrees up to a depth of
+/// [MAX_EMPTY_SUBTREE_DEPTH].
 fn combine_nodes_with_empty_hashes(
     nodes: impl IntoIterator<Item = InnerNodeInfo>,
 ) -> impl Iterator<Item = (RpoDigest, StoreNode)> {
@@ -1,8 +1,8 @@
 use seq_macro::seq;
 
 use super::{
-    DefaultMerkleStore as MerkleStore, EmptySubtreeRoots, MerkleError, MerklePath, NodeIndex,
-    PartialMerkleTree, RecordingMerkleStore, Rpo256, RpoDigest,
+    dedupe_witnesses, DefaultMerkleStore as MerkleStore, EmptySubtreeRoots, MerkleError,
+    MerklePath, NodeIndex, PartialMerkleTree, RecordingMerkleStore, Rpo256, RpoDigest,
 };
 use crate::{
     merkle::{
@@ -13,7 +13,7 @@ use crate::{
 
 #[cfg(feature = "std")]
 use {
-    super::{Deserializable, Serializable},
+    super::{Deserializable, MultiProof, Serializable},
     alloc::boxed::Box,
     std::error::Error,
 };
@@ -543,6 +543,38 @@ fn test_set_node() -> Result<(), MerkleError> {
     Ok(())
 }
 
+#[test]
+fn test_compute_root_after() -> Result<(), MerkleError> {
+    let mtree = MerkleTree::new(digests_to_words(&VALUES8))?;
+    let store = MerkleStore::from(&mtree);
+    let updates = [
+        (NodeIndex::make(3, 0), int_to_node(42)),
+        (NodeIndex::make(3, 1), int_to_node(43)),
+        (NodeIndex::make(2, 3), int_to_node(44)),
+    ];
+
+    let root = store.compute_root_after(mtree.root(), updates)?;
+
+    // the store is left untouched
+    assert_eq!(store, MerkleStore::from(&mtree));
+
+    // the computed root matches the one obtained by applying the updates
+    let mut updated = store.clone();
+    let mut expected = mtree.root();
+    for (index, value) in updates {
+        expected = updated.set_node(expected, index, value)?.root;
+    }
+    assert_eq!(root, expected);
+
+    // unknown roots are rejected
+    assert_eq!(
+        store.compute_root_after(expected, updates),
+        Err(MerkleError::RootNotInStore(expected))
+    );
+
+    Ok(())
+}
+
 #[test]
 fn test_constructors() -> Result<(), MerkleError> {
     let mtree = MerkleTree::new(digests_to_words(&VALUES4))?;
@@ -598,6 +630,45 @@ fn test_constructors() -> Result<(), MerkleError> {
     Ok(())
 }
 
+#[test]
+fn test_get_partial_tree() {
+    let mtree = MerkleTree::new(digests_to_words(&VALUES8)).unwrap();
+    let store = MerkleStore::from(&mtree);
+    let indices = [NodeIndex::make(3, 1), NodeIndex::make(3, 2), NodeIndex::make(2, 3)];
+
+    let pmt = store.get_partial_tree(mtree.root(), indices).unwrap();
+    assert_eq!(pmt.root(), mtree.root());
+    for index in indices {
+        assert_eq!(pmt.get_node(index), mtree.get_node(index));
+        assert_eq!(pmt.get_path(index), mtree.get_path(index));
+    }
+
+    // the partial tree can be converted back into a store holding the same openings
+    let pmt_store = MerkleStore::from(&pmt);
+    for index in indices {
+        assert_eq!(pmt_store.get_path(mtree.root(), index), store.get_path(mtree.root(), index));
+    }
+
+    assert_eq!(
+        store.get_partial_tree(RpoDigest::default(), indices),
+        Err(MerkleError::RootNotInStore(RpoDigest::default()))
+    );
+}
+
+#[test]
+fn test_with_capacity() -> Result<(), MerkleError> {
+    let mtree = MerkleTree::new(digests_to_words(&VALUES8))?;
+
+    let mut store = MerkleStore::with_capacity(mtree.inner_nodes().count());
+    assert_eq!(store, MerkleStore::new());
+
+    store.extend(mtree.inner_nodes());
+    store.reserve(10);
+    assert_eq!(store, MerkleStore::from(&mtree));
+
+    Ok(())
+}
+
 #[test]
 fn node_path_should_be_truncated_by_midtier_insert() {
     let key = 0b11010010_11001100_11001100_11001100_11001100_11001100_11001100_11001100_u64;
@@ -636,6 +707,128 @@ fn node_path_should_be_truncated_by_midtier_insert() {
     assert!(store.get_node(root, index).is_err());
 }
 
+#[test]
+fn get_path_partial_stops_at_empty_and_missing_nodes() -> Result<(), MerkleError> {
+    let smt = SimpleSmt::<3>::with_leaves([(0, int_to_leaf(1)), (1, int_to_leaf(2))])?;
+    let store = MerkleStore::from(&smt);
+
+    // the requested node is reachable, so the full path is returned
+    let index = NodeIndex::make(3, 1);
+    let (opening, depth) = store.get_path_partial(smt.root(), index)?;
+    assert_eq!(depth, 3);
+    assert_eq!(opening, store.get_path(smt.root(), index)?);
+
+    // the right half of the tree is empty, so the traversal stops at depth 1
+    let index = NodeIndex::make(3, 6);
+    let (opening, depth) = store.get_path_partial(smt.root(), index)?;
+    assert_eq!(depth, 1);
+    assert_eq!(opening.value, *EmptySubtreeRoots::entry(3, 1));
+    assert_eq!(opening.path.depth(), 1);
+    assert!(opening.path.verify(1, opening.value, &smt.root()));
+
+    // the leaves of the tree are not in the store, so a deeper index stops at the leaf
+    let index = NodeIndex::make(5, 4);
+    let (opening, depth) = store.get_path_partial(smt.root(), index)?;
+    assert_eq!(depth, 3);
+    assert_eq!(opening.value, RpoDigest::from(int_to_leaf(2)));
+
+    // an unknown root is still an error
+    assert_eq!(
+        store.get_path_partial(RpoDigest::from(int_to_leaf(1)), index),
+        Err(MerkleError::RootNotInStore(RpoDigest::from(int_to_leaf(1))))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn fingerprint_is_order_independent() -> Result<(), MerkleError> {
+    let mtree1 = MerkleTree::new(digests_to_words(&VALUES4))?;
+    let mtree2 = MerkleTree::new(digests_to_words(&VALUES8))?;
+
+    let mut store1 = MerkleStore::new();
+    store1.extend(mtree1.inner_nodes());
+    store1.extend(mtree2.inner_nodes());
+
+    let mut store2 = RecordingMerkleStore::new();
+    store2.extend(mtree2.inner_nodes());
+    store2.extend(mtree1.inner_nodes());
+
+    // the same set of nodes yields the same fingerprint regardless of insertion order and backend
+    assert_eq!(store1.fingerprint(), store2.fingerprint());
+
+    // any additional node changes the fingerprint
+    let fingerprint = store1.fingerprint();
+    store1.merge_roots(mtree1.root(), mtree2.root())?;
+    assert_ne!(store1.fingerprint(), fingerprint);
+
+    Ok(())
+}
+
+#[test]
+fn get_multiproof_shares_siblings() -> Result<(), MerkleError> {
+    let mtree = MerkleTree::new(digests_to_words(&VALUES8))?;
+    let store = MerkleStore::from(&mtree);
+
+    // leaves 0 and 1 are siblings, so only the two upper siblings of their parent are needed
+    let indices = [NodeIndex::make(3, 0), NodeIndex::make(3, 1)];
+    let proof = store.get_multiproof(mtree.root(), indices)?;
+    assert_eq!(proof.num_nodes(), 2);
+
+    let openings = [(indices[0], VALUES8[0]), (indices[1], VALUES8[1])];
+    assert!(proof.verify(openings, &mtree.root()));
+
+    // leaves 0 and 7 share no sibling below the root
+    let indices = [NodeIndex::make(3, 0), NodeIndex::make(3, 7)];
+    let proof = store.get_multiproof(mtree.root(), indices)?;
+    assert_eq!(proof.num_nodes(), 4);
+
+    let openings = [(indices[0], VALUES8[0]), (indices[1], VALUES8[7])];
+    assert_eq!(proof.compute_root(openings), Ok(mtree.root()));
+
+    // a wrong value doesn't verify, and a missing opening can't compute the root
+    assert!(!proof.verify([(indices[0], VALUES8[0]), (indices[1], VALUES8[6])], &mtree.root()));
+    assert!(proof.compute_root([(indices[0], VALUES8[0])]).is_err());
+
+    // an opening of a node which is also part of the proof must agree with it
+    let sibling = NodeIndex::make(3, 1);
+    assert_eq!(
+        proof.compute_root([
+            (indices[0], VALUES8[0]),
+            (indices[1], VALUES8[7]),
+            (sibling, VALUES8[2])
+        ]),
+        Err(MerkleError::ConflictingNodeValues {
+            index: sibling,
+            existing: VALUES8[1],
+            provided: VALUES8[2]
+        })
+    );
+
+    // nodes at different depths can be opened together
+    let indices = [NodeIndex::make(3, 2), NodeIndex::make(1, 1)];
+    let proof = store.get_multiproof(mtree.root(), indices)?;
+    let openings = indices.map(|index| (index, store.get_node(mtree.root(), index).unwrap()));
+    assert_eq!(proof.num_nodes(), 2);
+    assert!(proof.verify(openings, &mtree.root()));
+
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn multiproof_serialization() -> Result<(), Box<dyn Error>> {
+    let mtree = MerkleTree::new(digests_to_words(&VALUES8))?;
+    let store = MerkleStore::from(&mtree);
+
+    let indices = [NodeIndex::make(3, 1), NodeIndex::make(3, 4), NodeIndex::make(3, 5)];
+    let proof = store.get_multiproof(mtree.root(), indices)?;
+    let decoded = MultiProof::read_from_bytes(&proof.to_bytes())?;
+    assert_eq!(proof, decoded);
+
+    Ok(())
+}
+
 // LEAF TRAVERSAL
 // ================================================================================================
 
@@ -864,6 +1057,43 @@ fn check_mstore_subtree(store: &MerkleStore, subtree: &MerkleTree) {
     }
 }
 
+#[test]
+fn dedupe_witnesses_factors_common_nodes() -> Result<(), MerkleError> {
+    // the first two trees differ only on the last leaf, the third one is unrelated
+    let mut leaves = digests_to_words(&VALUES8);
+    let tree1 = MerkleTree::new(&leaves)?;
+    leaves[7] = int_to_leaf(9);
+    let tree2 = MerkleTree::new(&leaves)?;
+    let tree3 = MerkleTree::new(digests_to_words(&VALUES4))?;
+
+    let witnesses =
+        [MerkleStore::from(&tree1), MerkleStore::from(&tree2), MerkleStore::from(&tree3)];
+    let (shared, refs) = dedupe_witnesses(&witnesses);
+    assert_eq!(refs.len(), witnesses.len());
+
+    // the subtrees which don't contain the last leaf are shared by the first two witnesses
+    assert_eq!(shared.num_internal_nodes(), MerkleStore::new().num_internal_nodes() + 4);
+    assert_eq!(refs[0].shared_nodes().len(), 4);
+    assert_eq!(refs[0].unique_nodes().count(), 3);
+    assert_eq!(refs[1].shared_nodes().len(), 4);
+    assert_eq!(refs[1].unique_nodes().count(), 3);
+    assert!(refs[2].shared_nodes().is_empty());
+    assert_eq!(refs[2].unique_nodes().count(), 3);
+
+    // the original witnesses can be rebuilt from the references
+    for (witness, refs) in witnesses.iter().zip(refs.iter()) {
+        assert_eq!(&refs.to_store(&shared)?, witness);
+    }
+
+    // a witness referencing nodes missing from the shared store can't be rebuilt
+    assert_eq!(
+        refs[0].to_store(&MerkleStore::new()).unwrap_err(),
+        MerkleError::NodeHashNotInStore(refs[0].shared_nodes()[0])
+    );
+
+    Ok(())
+}
+
 // SERIALIZATION
 // ================================================================================================
 
@@ -0,0 +1,201 @@
+//! Pseudo-random functions and keystream generation based on the RPO permutation.
+
+use crate::{hash::rpo::Rpo256, Felt, Word, ONE, ZERO};
+
+// CONSTANTS
+// ================================================================================================
+
+const STATE_WIDTH: usize = Rpo256::STATE_WIDTH;
+const RATE_START: usize = Rpo256::RATE_RANGE.start;
+const RATE_END: usize = Rpo256::RATE_RANGE.end;
+const RATE_WIDTH: usize = RATE_END - RATE_START;
+const CAPACITY_START: usize = Rpo256::CAPACITY_RANGE.start;
+const DIGEST_START: usize = Rpo256::DIGEST_RANGE.start;
+
+/// Domain separator placed into the rate portion of the state right after the nonce when
+/// initializing an [RpoKeystream].
+///
+/// Since [rpo_prf] pads its input with a single ONE followed by ZEROs, using a value other than
+/// ZERO or ONE guarantees that the initial keystream state never matches the state of a PRF
+/// evaluation under the same key.
+const KEYSTREAM_DOMAIN: Felt = Felt::new(2);
+
+// PSEUDO-RANDOM FUNCTION
+// ================================================================================================
+
+/// Evaluates a pseudo-random function keyed by `key` on the provided input.
+///
+/// The function is a keyed sponge: the key is placed into the capacity portion of the RPO state,
+/// the input is absorbed into the rate portion, and the result is squeezed from the digest range.
+/// The input is always padded with a single ONE followed by ZEROs, so inputs of different lengths
+/// never collide.
+pub fn rpo_prf(key: Word, input: &[Felt]) -> Word {
+    let mut state = [ZERO; STATE_WIDTH];
+    state[CAPACITY_START..CAPACITY_START + key.len()].copy_from_slice(&key);
+
+    // absorb the input followed by the padding; the final block is never empty, since the padding
+    // element is always present
+    let mut i = 0;
+    for element in input.iter().copied().chain([ONE]) {
+        state[RATE_START + i] += element;
+        i += 1;
+        if i == RATE_WIDTH {
+            Rpo256::apply_permutation(&mut state);
+            i = 0;
+        }
+    }
+    if i != 0 {
+        Rpo256::apply_permutation(&mut state);
+    }
+
+    state[DIGEST_START..DIGEST_START + 4].try_into().unwrap()
+}
+
+// RPO KEYSTREAM
+// ================================================================================================
+
+/// A keystream generator built on the RPO sponge in duplex mode.
+///
+/// The sponge is keyed by placing the key into its capacity portion, and the nonce is absorbed
+/// into its rate portion. The keystream is then squeezed from the rate portion of the state, with
+/// the permutation applied whenever the rate has been fully consumed. Additional data can be
+/// absorbed between squeezes via [RpoKeystream::absorb], which makes all subsequent keystream
+/// elements depend on it.
+///
+/// A given (key, nonce) pair must never be used to mask more than one sequence of values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpoKeystream {
+    state: [Felt; STATE_WIDTH],
+    current: usize,
+}
+
+impl RpoKeystream {
+    /// Returns a new [RpoKeystream] for the specified key and nonce.
+    pub fn new(key: Word, nonce: Word) -> Self {
+        let mut state = [ZERO; STATE_WIDTH];
+        state[CAPACITY_START..CAPACITY_START + key.len()].copy_from_slice(&key);
+        state[RATE_START..RATE_START + nonce.len()].copy_from_slice(&nonce);
+        state[RATE_START + nonce.len()] = KEYSTREAM_DOMAIN;
+
+        Rpo256::apply_permutation(&mut state);
+
+        Self { state, current: RATE_START }
+    }
+
+    /// Absorbs the provided data into the state of the sponge.
+    ///
+    /// Any keystream elements remaining in the current rate are discarded.
+    pub fn absorb(&mut self, data: Word) {
+        for (i, element) in data.iter().enumerate() {
+            self.state[RATE_START + i] += *element;
+        }
+
+        Rpo256::apply_permutation(&mut self.state);
+        self.current = RATE_START;
+    }
+
+    /// Returns the next element of the keystream.
+    pub fn next_element(&mut self) -> Felt {
+        if self.current == RATE_END {
+            Rpo256::apply_permutation(&mut self.state);
+            self.current = RATE_START;
+        }
+
+        self.current += 1;
+        self.state[self.current - 1]
+    }
+
+    /// Returns the next four elements of the keystream.
+    pub fn next_word(&mut self) -> Word {
+        let mut output = [ZERO; 4];
+        for o in output.iter_mut() {
+            *o = self.next_element();
+        }
+        output
+    }
+
+    /// Masks the provided values by adding the next elements of the keystream to them.
+    pub fn apply_mask(&mut self, values: &mut [Felt]) {
+        for value in values.iter_mut() {
+            *value += self.next_element();
+        }
+    }
+
+    /// Removes a mask applied by [RpoKeystream::apply_mask] with the same key and nonce, by
+    /// subtracting the next elements of the keystream from the provided values.
+    pub fn remove_mask(&mut self, values: &mut [Felt]) {
+        for value in values.iter_mut() {
+            *value -= self.next_element();
+        }
+    }
+}
+
+impl Iterator for RpoKeystream {
+    type Item = Felt;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_element())
+    }
+}
+
+// TESTS
+// ================================================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::{rpo_prf, RpoKeystream, ZERO};
+    use crate::{Felt, Word, ONE};
+    use alloc::vec::Vec;
+
+    const KEY: Word = [Felt::new(1), Felt::new(2), Felt::new(3), Felt::new(4)];
+    const NONCE: Word = [Felt::new(5), Felt::new(6), Felt::new(7), Felt::new(8)];
+
+    #[test]
+    fn test_prf_depends_on_key_and_input() {
+        let input: Vec<Felt> = (0..10).map(Felt::new).collect();
+        let output = rpo_prf(KEY, &input);
+        assert_eq!(output, rpo_prf(KEY, &input));
+
+        let other_key = [ONE, ZERO, ZERO, ZERO];
+        assert_ne!(output, rpo_prf(other_key, &input));
+        assert_ne!(output, rpo_prf(KEY, &input[..9]));
+
+        // trailing zeros are not absorbed by the padding
+        assert_ne!(rpo_prf(KEY, &[]), rpo_prf(KEY, &[ZERO]));
+        assert_ne!(rpo_prf(KEY, &input[..8]), rpo_prf(KEY, &[&input[..8], &[ZERO][..]].concat()));
+    }
+
+    #[test]
+    fn test_keystream_mask_roundtrip() {
+        let values: Vec<Felt> = (0..20).map(Felt::new).collect();
+
+        let mut masked = values.clone();
+        RpoKeystream::new(KEY, NONCE).apply_mask(&mut masked);
+        assert_ne!(masked, values);
+
+        let mut unmasked = masked.clone();
+        RpoKeystream::new(KEY, NONCE).remove_mask(&mut unmasked);
+        assert_eq!(unmasked, values);
+
+        // a different nonce produces a different keystream
+        let mut other = values.clone();
+        RpoKeystream::new(KEY, [ZERO; 4]).apply_mask(&mut other);
+        assert_ne!(other, masked);
+
+        // absorbed data changes the remainder of the keystream
+        let mut keystream = RpoKeystream::new(KEY, NONCE);
+        let mut duplexed = keystream.clone();
+        duplexed.absorb([ONE; 4]);
+        assert_ne!(keystream.next_word(), duplexed.next_word());
+    }
+
+    #[test]
+    fn test_keystream_is_consistent_across_calls() {
+        let mut keystream = RpoKeystream::new(KEY, NONCE);
+        let expected: Vec<Felt> = keystream.clone().take(20).collect();
+
+        let mut actual = keystream.next_word().to_vec();
+        actual.extend(keystream.by_ref().take(16));
+        assert_eq!(actual, expected);
+    }
+}
@@ -8,19 +8,55 @@ use core::cell::RefCell;
 // ================================================================================================
 
 /// A trait that defines the interface for a key-value map.
+///
+/// This is the storage abstraction used by [MerkleStore](crate::merkle::MerkleStore). Any map can
+/// be used as the backend of a store, as long as:
+/// - it can be built from, extended with, and consumed into `(key, value)` pairs;
+/// - lookups return plain references into the map. Maps which hand out lock guards instead (e.g.
+///   `dashmap`) need a thin wrapper holding the data for the duration of the borrow;
+/// - `insert` and `remove` return the previous value under the key, if any.
+///
+/// Implementations are provided for [BTreeMap], [RecordingMap] and, when the `indexmap` feature is
+/// enabled, `indexmap::IndexMap`.
 pub trait KvMap<K: Ord + Clone, V: Clone>:
     Extend<(K, V)> + FromIterator<(K, V)> + IntoIterator<Item = (K, V)>
 {
+    /// Returns a reference to the value associated with `key`, if any.
     fn get(&self, key: &K) -> Option<&V>;
+
+    /// Returns `true` if the map contains a value for `key`.
     fn contains_key(&self, key: &K) -> bool;
+
+    /// Returns the number of key-value pairs in the map.
     fn len(&self) -> usize;
+
+    /// Returns `true` if the map contains no key-value pairs.
     fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Inserts a key-value pair into the map, returning the previous value under `key`, if any.
     fn insert(&mut self, key: K, value: V) -> Option<V>;
+
+    /// Removes `key` from the map, returning its value, if any.
     fn remove(&mut self, key: &K) -> Option<V>;
 
+    /// Returns an iterator over the key-value pairs of the map.
+    ///
+    /// The iteration order is defined by the implementation.
     fn iter(&self) -> Box<dyn Iterator<Item = (&K, &V)> + '_>;
+
+    /// Returns a new empty map with space for at least `capacity` key-value pairs.
+    ///
+    /// Maps which cannot be pre-allocated ignore the capacity hint.
+    fn with_capacity(_capacity: usize) -> Self {
+        core::iter::empty().collect()
+    }
+
+    /// Reserves space for at least `additional` more key-value pairs.
+    ///
+    /// Maps which cannot be pre-allocated ignore the capacity hint.
+    fn reserve(&mut self, _additional: usize) {}
 }
 
 // BTREE MAP `KvMap` IMPLEMENTATION
@@ -52,6 +88,68 @@ impl<K: Ord + Clone, V: Clone> KvMap<K, V> for BTreeMap<K, V> {
     }
 }
 
+// INDEX MAP `KvMap` IMPLEMENTATION
+// ================================================================================================
+
+/// An insertion-ordered [KvMap] backed by an [IndexMap](indexmap::IndexMap).
+///
+/// Removals shift the remaining entries so that their relative order is preserved.
+///
+/// ```
+/// # use indexmap::IndexMap;
+/// # use miden_crypto::{hash::rpo::RpoDigest, merkle::{MerkleStore, MerkleTree, StoreNode}};
+/// # use miden_crypto::{Felt, ONE, ZERO};
+/// let leaves = [[ONE, ZERO, ZERO, ZERO], [Felt::new(2), ZERO, ZERO, ZERO]];
+/// let tree = MerkleTree::new(leaves).unwrap();
+///
+/// let mut store: MerkleStore<IndexMap<RpoDigest, StoreNode>> = MerkleStore::new();
+/// let num_empty_nodes = store.num_internal_nodes();
+/// store.extend(tree.inner_nodes());
+///
+/// // nodes are iterated in the order they were added to the store
+/// let added = store.inner_nodes().nth(num_empty_nodes).unwrap();
+/// assert_eq!(added.value, tree.root());
+/// ```
+#[cfg(feature = "indexmap")]
+impl<K, V, S> KvMap<K, V> for indexmap::IndexMap<K, V, S>
+where
+    K: Ord + Clone + core::hash::Hash,
+    V: Clone,
+    S: core::hash::BuildHasher + Default,
+{
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+
+    fn contains_key(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert(key, value)
+    }
+
+    fn remove(&mut self, key: &K) -> Option<V> {
+        self.shift_remove(key)
+    }
+
+    fn iter(&self) -> Box<dyn Iterator<Item = (&K, &V)> + '_> {
+        Box::new(self.iter())
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, S::default())
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.reserve(additional)
+    }
+}
+
 // RECORDING MAP
 // ================================================================================================
 
@@ -45,12 +45,46 @@ pub fn bytes_to_hex_string<const N: usize>(data: [u8; N]) -> String {
     s
 }
 
+/// Writes the provided bytes as hex digits, prefixed with `0x` when the alternate flag of the
+/// formatter is set (i.e., `{:#x}`).
+pub(crate) fn fmt_hex(bytes: &[u8], f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    if f.alternate() {
+        f.write_str("0x")?;
+    }
+    for byte in bytes {
+        write!(f, "{byte:02x}")?;
+    }
+    Ok(())
+}
+
+/// Returns true if the provided byte slices are equal, in time independent of their contents.
+///
+/// Every byte is read with a volatile read, which the compiler can neither elide nor reorder, so
+/// that it can't turn the comparison into one returning as soon as two bytes differ.
+pub(crate) fn ct_eq_bytes(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut diff = 0_u8;
+    for (x, y) in a.iter().zip(b.iter()) {
+        // SAFETY: both pointers are derived from references, so they are valid for reads and
+        // properly aligned.
+        diff |= unsafe { core::ptr::read_volatile(x) ^ core::ptr::read_volatile(y) };
+    }
+    diff == 0
+}
+
 /// Defines errors which can occur during parsing of hexadecimal strings.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum HexParseError {
+    /// The hex string does not have the expected length, including the `0x` prefix.
     InvalidLength { expected: usize, actual: usize },
+    /// The hex string does not start with `0x`.
     MissingPrefix,
+    /// The hex string contains a character which is not a hexadecimal digit.
     InvalidChar,
+    /// The decoded bytes are not the canonical encoding of a field element.
     OutOfRange,
 }
 
@@ -58,16 +92,16 @@ impl Display for HexParseError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             HexParseError::InvalidLength { expected, actual } => {
-                write!(f, "Hex encoded RpoDigest must have length 66, including the 0x prefix. expected {expected} got {actual}")
+                write!(f, "Hex encoded value must have length {expected}, including the 0x prefix, but has length {actual}")
             }
             HexParseError::MissingPrefix => {
-                write!(f, "Hex encoded RpoDigest must start with 0x prefix")
+                write!(f, "Hex encoded value must start with 0x prefix")
             }
             HexParseError::InvalidChar => {
-                write!(f, "Hex encoded RpoDigest must contain characters [a-zA-Z0-9]")
+                write!(f, "Hex encoded value must contain only characters [a-fA-F0-9]")
             }
             HexParseError::OutOfRange => {
-                write!(f, "Hex encoded values of an RpoDigest must be inside the field modulus")
+                write!(f, "Hex encoded field elements must be inside the field modulus")
             }
         }
     }
@@ -76,6 +110,32 @@ impl Display for HexParseError {
 #[cfg(feature = "std")]
 impl std::error::Error for HexParseError {}
 
+/// Defines errors which can occur when decoding digests or their field elements from bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DigestDecodingError {
+    /// The input does not have the length of an encoded digest.
+    InvalidLength { expected: usize, actual: usize },
+    /// The bytes of an element are not its canonical encoding, i.e., they encode an integer which
+    /// is not smaller than the field modulus.
+    NonCanonicalElement,
+}
+
+impl Display for DigestDecodingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DigestDecodingError::InvalidLength { expected, actual } => {
+                write!(f, "Encoded digest must have length {expected}, but has length {actual}")
+            }
+            DigestDecodingError::NonCanonicalElement => {
+                write!(f, "Encoded field elements must be inside the field modulus")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DigestDecodingError {}
+
 /// Parses a hex string into an array of bytes of known size.
 pub fn hex_to_bytes<const N: usize>(value: &str) -> Result<[u8; N], HexParseError> {
     let expected: usize = (N * 2) + 2;